@@ -0,0 +1,269 @@
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+
+use crate::config::Config;
+
+/// Confirmed answers for a day, stored in `answers/<year>/NN` under the data directory, next to
+/// the `input/<year>/NN` cache.
+///
+/// The file has one `partN: <answer>` line per confirmed part, the same layout as the example
+/// fixtures used by the tests. Answers the site rejected are kept as `rejectedN: <answer> [hint]`
+/// lines.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+    pub rejected: Vec<Rejection>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+impl Hint {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Hint::TooHigh => "too high",
+            Hint::TooLow => "too low",
+        }
+    }
+}
+
+/// An answer the site said was wrong, with its high/low hint if it gave one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejection {
+    pub part: u8,
+    pub answer: String,
+    pub hint: Option<Hint>,
+}
+
+/// The result of comparing a fresh answer against the store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Match,
+    Mismatch(String),
+    Unknown,
+}
+
+fn store_path(config: &Config, day: u8) -> std::path::PathBuf {
+    config.year_dir("answers").join(format!("{day:02}"))
+}
+
+impl std::str::FromStr for Answers {
+    type Err = color_eyre::eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = Answers::default();
+
+        for line in s.lines().filter(|l| !l.trim().is_empty()) {
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| eyre!("Invalid answer line: {line:?}"))?;
+            let value = value.trim();
+
+            match key.trim() {
+                "part1" => answers.set(1, value)?,
+                "part2" => answers.set(2, value)?,
+                "rejected1" | "rejected2" => {
+                    let part = if key.trim() == "rejected1" { 1 } else { 2 };
+                    let (answer, hint) = match value.split_once(' ') {
+                        Some((answer, "too high")) => (answer, Some(Hint::TooHigh)),
+                        Some((answer, "too low")) => (answer, Some(Hint::TooLow)),
+                        Some(_) => return Err(eyre!("Invalid rejection: {value:?}")),
+                        None => (value, None),
+                    };
+                    answers.reject(part, answer, hint);
+                }
+                // The cooldown used to be stored per day, but it is account-wide.
+                "cooldown" => {}
+                k => return Err(eyre!("Invalid key: {k:?}")),
+            }
+        }
+
+        Ok(answers)
+    }
+}
+
+impl std::fmt::Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(answer) = &self.part1 {
+            writeln!(f, "part1: {answer}")?;
+        }
+
+        if let Some(answer) = &self.part2 {
+            writeln!(f, "part2: {answer}")?;
+        }
+
+        for r in &self.rejected {
+            write!(f, "rejected{}: {}", r.part, r.answer)?;
+            if let Some(hint) = r.hint {
+                write!(f, " {}", hint.as_str())?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Answers {
+    /// Loads the answers for `day`, treating a missing file as nothing confirmed yet.
+    pub fn load(config: &Config, day: u8) -> Result<Self> {
+        let path = store_path(config, day);
+        if path.exists() {
+            std::fs::read_to_string(path)?.parse()
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self, config: &Config, day: u8) -> Result<()> {
+        let path = store_path(config, day);
+        let root = path.parent().unwrap();
+        if !root.exists() {
+            std::fs::create_dir_all(root)?;
+        }
+        std::fs::write(&path, self.to_string())?;
+
+        Ok(())
+    }
+
+    pub fn get(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn set(&mut self, part: u8, answer: &str) -> Result<()> {
+        let slot = match part {
+            1 => &mut self.part1,
+            2 => &mut self.part2,
+            _ => return Err(eyre!("Invalid part: {part}")),
+        };
+        *slot = Some(answer.to_string());
+
+        Ok(())
+    }
+
+    pub fn reject(&mut self, part: u8, answer: &str, hint: Option<Hint>) {
+        if !self
+            .rejected
+            .iter()
+            .any(|r| r.part == part && r.answer == answer)
+        {
+            self.rejected.push(Rejection {
+                part,
+                answer: answer.to_string(),
+                hint,
+            });
+        }
+    }
+
+    /// Explains why submitting `answer` is pointless given what we already know, if it is.
+    pub fn refusal(&self, part: u8, answer: &str) -> Option<String> {
+        match self.get(part) {
+            Some(confirmed) if confirmed == answer => {
+                return Some(format!("{answer} is already the confirmed answer"))
+            }
+            Some(confirmed) => {
+                return Some(format!("part {part} is already solved with {confirmed}"))
+            }
+            None => {}
+        }
+
+        let value = answer.parse::<i128>().ok();
+        self.rejected
+            .iter()
+            .filter(|r| r.part == part)
+            .find_map(|r| {
+                if r.answer == answer {
+                    return Some(format!("{answer} was already rejected"));
+                }
+
+                let rejected = r.answer.parse::<i128>().ok()?;
+                match r.hint? {
+                    Hint::TooHigh if value? >= rejected => {
+                        Some(format!("{rejected} was already too high"))
+                    }
+                    Hint::TooLow if value? <= rejected => {
+                        Some(format!("{rejected} was already too low"))
+                    }
+                    _ => None,
+                }
+            })
+    }
+
+    pub fn check(&self, part: u8, answer: &str) -> Verdict {
+        match self.get(part) {
+            Some(expected) if expected == answer => Verdict::Match,
+            Some(expected) => Verdict::Mismatch(expected.to_string()),
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut answers = Answers {
+            part1: Some("142".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(answers.to_string(), "part1: 142\n");
+        assert_eq!(answers.to_string().parse::<Answers>().unwrap(), answers);
+
+        answers.reject(2, "300", Some(Hint::TooHigh));
+        answers.reject(2, "abc", None);
+
+        assert_eq!(
+            answers.to_string(),
+            "part1: 142\nrejected2: 300 too high\nrejected2: abc\n"
+        );
+        assert_eq!(answers.to_string().parse::<Answers>().unwrap(), answers);
+    }
+
+    #[test]
+    fn refusal() {
+        let mut answers = Answers::default();
+        answers.reject(1, "300", Some(Hint::TooHigh));
+        answers.reject(1, "100", Some(Hint::TooLow));
+        answers.reject(1, "200", None);
+
+        assert!(answers.refusal(1, "300").is_some());
+        assert!(answers.refusal(1, "301").is_some());
+        assert!(answers.refusal(1, "99").is_some());
+        assert!(answers.refusal(1, "200").is_some());
+        assert_eq!(answers.refusal(1, "150"), None);
+        assert_eq!(answers.refusal(2, "300"), None);
+
+        answers.set(1, "150").unwrap();
+        assert!(answers.refusal(1, "150").is_some());
+        assert!(answers.refusal(1, "151").is_some());
+    }
+
+    #[test]
+    fn check() {
+        let answers: Answers = "part2: 281\n".parse().unwrap();
+
+        assert_eq!(answers.check(1, "142"), Verdict::Unknown);
+        assert_eq!(answers.check(2, "281"), Verdict::Match);
+        assert_eq!(
+            answers.check(2, "280"),
+            Verdict::Mismatch("281".to_string())
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!("part3: 1".parse::<Answers>().is_err());
+        assert!("142".parse::<Answers>().is_err());
+    }
+}
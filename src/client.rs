@@ -0,0 +1,50 @@
+use color_eyre::Result;
+
+use crate::config::Config;
+
+/// Talks to the Advent of Code website (or a stand-in for it) using the session cookie.
+#[derive(Debug)]
+pub struct Client {
+    event_url: String,
+    session: String,
+    http: reqwest::blocking::Client,
+}
+
+impl Client {
+    pub fn new(config: &Config) -> Result<Self> {
+        let http = reqwest::blocking::ClientBuilder::new()
+            .user_agent(config.user_agent())
+            .build()?;
+
+        Ok(Self {
+            event_url: config.event_url(),
+            session: config.session()?,
+            http,
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.event_url, path.trim_start_matches('/'))
+    }
+
+    pub fn get(&self, path: &str) -> Result<reqwest::blocking::Response> {
+        Ok(self
+            .http
+            .get(self.url(path))
+            .header("cookie", format!("session={}", self.session))
+            .send()?)
+    }
+
+    pub fn post_form(
+        &self,
+        path: &str,
+        form: &[(&str, &str)],
+    ) -> Result<reqwest::blocking::Response> {
+        Ok(self
+            .http
+            .post(self.url(path))
+            .header("cookie", format!("session={}", self.session))
+            .form(form)
+            .send()?)
+    }
+}
@@ -0,0 +1,293 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_YEAR: u16 = 2023;
+
+/// Where the website lives and how we identify ourselves to it.
+///
+/// Values come from a `key = value` config file and are overridden by environment variables:
+///
+/// | key            | variable           | default                     |
+/// |----------------|--------------------|-----------------------------|
+/// | `base_url`     | `AOC_BASE_URL`     | `https://adventofcode.com`  |
+/// | `year`         | `AOC_YEAR`         | `2023`                      |
+/// | `session_file` | `AOC_SESSION_FILE` | `session_token`             |
+/// | `contact`      | `AOC_CONTACT`      | none                        |
+/// | `data_dir`     | `AOC_DATA_DIR`     | see below                   |
+///
+/// `AOC_SESSION` holds the session token itself and takes precedence over `session_file`. Relative
+/// paths in the config file are resolved against the directory of the config file, and any other
+/// relative `session_file` against `data_dir`. With a config file, the default `session_file` sits
+/// next to it.
+///
+/// The config file is `AOC_CONFIG` if set, otherwise `advent2023.conf` in the current directory,
+/// otherwise `advent2023/config` in the user's config directory.
+///
+/// Cached inputs, answers and the request throttle live under `data_dir`. It defaults to the
+/// directory of the config file, or `advent2023/` in the user's data directory when the config
+/// file is the one in the user's config directory. Without a config file it is the current
+/// directory, as it was before any of this was configurable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub base_url: String,
+    pub year: u16,
+    pub session: Option<String>,
+    pub session_file: PathBuf,
+    pub contact: Option<String>,
+    pub data_dir: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            year: DEFAULT_YEAR,
+            session: None,
+            session_file: "session_token".into(),
+            contact: None,
+            data_dir: ".".into(),
+        }
+    }
+}
+
+fn config_home() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+}
+
+fn data_home() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))
+}
+
+/// The config file in the user's config directory.
+fn user_config_file() -> Option<PathBuf> {
+    Some(config_home()?.join("advent2023").join("config"))
+}
+
+fn config_file() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("AOC_CONFIG") {
+        return Some(path.into());
+    }
+
+    let local = PathBuf::from("advent2023.conf");
+    if local.exists() {
+        return Some(local);
+    }
+
+    user_config_file().filter(|p| p.exists())
+}
+
+impl Config {
+    /// Loads the config file, if any, and applies environment overrides.
+    pub fn load() -> Result<Self> {
+        let mut config = Self::default();
+
+        if let Some(path) = config_file() {
+            let contents = std::fs::read_to_string(&path)
+                .map_err(|e| eyre!("Could not read config {}: {e}", path.display()))?;
+            let path = std::fs::canonicalize(&path)?;
+            let root = path.parent().unwrap_or(Path::new("/"));
+
+            // Cached state does not belong in the user's config directory.
+            let in_config_home = user_config_file()
+                .and_then(|p| std::fs::canonicalize(p).ok())
+                .is_some_and(|p| p == path);
+            config.data_dir = match data_home() {
+                Some(home) if in_config_home => home.join("advent2023"),
+                _ => root.to_path_buf(),
+            };
+            config.session_file = root.join(&config.session_file);
+            config.apply_file(&contents, root)?;
+        }
+
+        config.apply_env(|key| std::env::var(key).ok())?;
+
+        Ok(config)
+    }
+
+    fn apply_file(&mut self, contents: &str, root: &Path) -> Result<()> {
+        for line in contents.lines() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| eyre!("Invalid config line: {line:?}"))?;
+            let value = value.trim().trim_matches('"');
+
+            match key.trim() {
+                "base_url" => self.base_url = value.to_string(),
+                "year" => self.year = value.parse()?,
+                "session_file" => self.session_file = root.join(value),
+                "contact" => self.contact = Some(value.to_string()),
+                "data_dir" => self.data_dir = root.join(value),
+                k => return Err(eyre!("Unknown config key: {k:?}")),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<()> {
+        if let Some(base_url) = var("AOC_BASE_URL") {
+            self.base_url = base_url;
+        }
+        if let Some(year) = var("AOC_YEAR") {
+            self.year = year.parse()?;
+        }
+        if let Some(session) = var("AOC_SESSION") {
+            self.session = Some(session);
+        }
+        if let Some(path) = var("AOC_SESSION_FILE") {
+            self.session_file = path.into();
+        }
+        if let Some(contact) = var("AOC_CONTACT") {
+            self.contact = Some(contact);
+        }
+        if let Some(path) = var("AOC_DATA_DIR") {
+            self.data_dir = path.into();
+        }
+
+        Ok(())
+    }
+
+    /// The event root, e.g. `https://adventofcode.com/2023`.
+    pub fn event_url(&self) -> String {
+        format!("{}/{}", self.base_url.trim_end_matches('/'), self.year)
+    }
+
+    /// Where state of `kind` (e.g. `input` or `answers`) for the configured year is kept.
+    ///
+    /// State used to be kept directly in `kind/`, which always meant 2023, so files there are
+    /// still used for 2023 until a year-keyed directory exists.
+    pub fn year_dir(&self, kind: &str) -> PathBuf {
+        let unkeyed = self.data_dir.join(kind);
+        let keyed = unkeyed.join(self.year.to_string());
+
+        let has_files = |dir: &Path| {
+            std::fs::read_dir(dir)
+                .into_iter()
+                .flatten()
+                .any(|entry| entry.is_ok_and(|e| e.path().is_file()))
+        };
+        if self.year == DEFAULT_YEAR && !keyed.exists() && has_files(&unkeyed) {
+            unkeyed
+        } else {
+            keyed
+        }
+    }
+
+    pub fn session(&self) -> Result<String> {
+        let path = self.data_dir.join(&self.session_file);
+        let session = match &self.session {
+            Some(session) => session.clone(),
+            None => std::fs::read_to_string(&path).map_err(|e| {
+                eyre!(
+                    "Could not read session token from {} (or set AOC_SESSION): {e}",
+                    path.display()
+                )
+            })?,
+        };
+
+        Ok(session.trim().to_string())
+    }
+
+    pub fn user_agent(&self) -> String {
+        let agent = "github.com/OneFourth/advent2023";
+        match &self.contact {
+            Some(contact) => format!("{agent} by {contact}"),
+            None => agent.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_and_env() {
+        let mut config = Config::default();
+        config
+            .apply_file(
+                "# team config\nyear = 2022\nsession_file = secrets/token\ncontact = \"a@b.c\"\n",
+                Path::new("/etc/aoc"),
+            )
+            .unwrap();
+
+        assert_eq!(config.year, 2022);
+        assert_eq!(config.session_file, Path::new("/etc/aoc/secrets/token"));
+        assert_eq!(
+            config.user_agent(),
+            "github.com/OneFourth/advent2023 by a@b.c"
+        );
+
+        config
+            .apply_env(|key| match key {
+                "AOC_BASE_URL" => Some("http://127.0.0.1:8080/".to_string()),
+                "AOC_SESSION" => Some(" abc\n".to_string()),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(config.event_url(), "http://127.0.0.1:8080/2022");
+        assert_eq!(config.session().unwrap(), "abc");
+    }
+
+    #[test]
+    fn state_is_keyed_by_year() {
+        let mut config = Config::default();
+        config
+            .apply_file("data_dir = state\nyear = 2022\n", Path::new("/etc/aoc"))
+            .unwrap();
+
+        assert_eq!(
+            config.year_dir("input"),
+            Path::new("/etc/aoc/state/input/2022")
+        );
+
+        config
+            .apply_env(|key| (key == "AOC_YEAR").then(|| "2023".to_string()))
+            .unwrap();
+        assert_eq!(
+            config.year_dir("input"),
+            Path::new("/etc/aoc/state/input/2023")
+        );
+    }
+
+    #[test]
+    fn unkeyed_state_is_still_used() {
+        let root = std::env::temp_dir().join(format!("config-test-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("input")).unwrap();
+        std::fs::write(root.join("input/01"), "1abc2\n").unwrap();
+
+        let mut config = Config {
+            data_dir: root.clone(),
+            ..Config::default()
+        };
+        assert_eq!(config.year_dir("input"), root.join("input"));
+        assert_eq!(config.year_dir("answers"), root.join("answers/2023"));
+
+        config.year = 2022;
+        assert_eq!(config.year_dir("input"), root.join("input/2022"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn invalid_file() {
+        let mut config = Config::default();
+        assert!(config.apply_file("token = abc", Path::new("")).is_err());
+        assert!(config.apply_file("year", Path::new("")).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use color_eyre::eyre::eyre;
 use color_eyre::Result;
 
 mod day01;
@@ -25,40 +26,239 @@ mod day22;
 mod day23;
 mod day24;
 mod day25;
+mod graph;
+mod polygon;
+mod search;
 
 pub trait Day {
-    fn setup(&mut self) -> Result<()>;
+    fn setup(&mut self, input: &str) -> Result<()>;
     fn part1(&self) -> Result<String>;
     fn part2(&self) -> Result<String>;
+
+    /// A picture of the solved puzzle, for days that can draw one.
+    fn visualize(&self) -> Option<String> {
+        None
+    }
+
+    /// Intermediate results for debugging a solution, available straight after `setup`.
+    fn report(&self) -> Option<String> {
+        None
+    }
 }
 
-pub fn get(day: u8) -> Box<dyn Day> {
-    match day {
-        1 => Box::<day01::Day01>::default(),
-        2 => Box::<day02::Day02>::default(),
-        3 => Box::<day03::Day03>::default(),
-        4 => Box::<day04::Day04>::default(),
-        5 => Box::<day05::Day05>::default(),
-        6 => Box::<day06::Day06>::default(),
-        7 => Box::<day07::Day07>::default(),
-        8 => Box::<day08::Day08>::default(),
-        9 => Box::<day09::Day09>::default(),
-        10 => Box::<day10::Day10>::default(),
-        11 => Box::<day11::Day11>::default(),
-        12 => Box::<day12::Day12>::default(),
-        13 => Box::<day13::Day13>::default(),
-        14 => Box::<day14::Day14>::default(),
-        15 => Box::<day15::Day15>::default(),
-        16 => Box::<day16::Day16>::default(),
-        17 => Box::<day17::Day17>::default(),
-        18 => Box::<day18::Day18>::default(),
-        19 => Box::<day19::Day19>::default(),
-        20 => Box::<day20::Day20>::default(),
-        21 => Box::<day21::Day21>::default(),
-        22 => Box::<day22::Day22>::default(),
-        23 => Box::<day23::Day23>::default(),
-        24 => Box::<day24::Day24>::default(),
-        25 => Box::<day25::Day25>::default(),
-        _ => panic!("Invalid day"),
+/// Metadata about a registered day and a constructor for its solver.
+#[derive(Debug, Clone, Copy)]
+pub struct Info {
+    pub number: u8,
+    pub title: &'static str,
+    pub part1: bool,
+    pub part2: bool,
+    create: fn() -> Box<dyn Day>,
+}
+
+impl Info {
+    pub fn create(&self) -> Box<dyn Day> {
+        (self.create)()
+    }
+
+    /// A stub has neither part implemented, so even its `setup` is a `todo!()`.
+    pub fn is_stub(&self) -> bool {
+        !self.part1 && !self.part2
     }
 }
+
+fn create<D: Day + Default + 'static>() -> Box<dyn Day> {
+    Box::<D>::default()
+}
+
+const REGISTRY: [Info; 25] = [
+    Info {
+        number: 1,
+        title: "Trebuchet?!",
+        part1: true,
+        part2: true,
+        create: create::<day01::Day01>,
+    },
+    Info {
+        number: 2,
+        title: "Cube Conundrum",
+        part1: true,
+        part2: true,
+        create: create::<day02::Day02>,
+    },
+    Info {
+        number: 3,
+        title: "Gear Ratios",
+        part1: true,
+        part2: true,
+        create: create::<day03::Day03>,
+    },
+    Info {
+        number: 4,
+        title: "Scratchcards",
+        part1: true,
+        part2: true,
+        create: create::<day04::Day04>,
+    },
+    Info {
+        number: 5,
+        title: "If You Give A Seed A Fertilizer",
+        part1: true,
+        part2: true,
+        create: create::<day05::Day05>,
+    },
+    Info {
+        number: 6,
+        title: "Wait For It",
+        part1: true,
+        part2: true,
+        create: create::<day06::Day06>,
+    },
+    Info {
+        number: 7,
+        title: "Camel Cards",
+        part1: true,
+        part2: true,
+        create: create::<day07::Day07>,
+    },
+    Info {
+        number: 8,
+        title: "Haunted Wasteland",
+        part1: true,
+        part2: true,
+        create: create::<day08::Day08>,
+    },
+    Info {
+        number: 9,
+        title: "Mirage Maintenance",
+        part1: true,
+        part2: true,
+        create: create::<day09::Day09>,
+    },
+    Info {
+        number: 10,
+        title: "Pipe Maze",
+        part1: true,
+        part2: true,
+        create: create::<day10::Day10>,
+    },
+    Info {
+        number: 11,
+        title: "Cosmic Expansion",
+        part1: true,
+        part2: true,
+        create: create::<day11::Day11>,
+    },
+    Info {
+        number: 12,
+        title: "Hot Springs",
+        part1: true,
+        part2: true,
+        create: create::<day12::Day12>,
+    },
+    Info {
+        number: 13,
+        title: "Point of Incidence",
+        part1: true,
+        part2: true,
+        create: create::<day13::Day13>,
+    },
+    Info {
+        number: 14,
+        title: "Parabolic Reflector Dish",
+        part1: true,
+        part2: true,
+        create: create::<day14::Day14>,
+    },
+    Info {
+        number: 15,
+        title: "Lens Library",
+        part1: true,
+        part2: true,
+        create: create::<day15::Day15>,
+    },
+    Info {
+        number: 16,
+        title: "The Floor Will Be Lava",
+        part1: true,
+        part2: true,
+        create: create::<day16::Day16>,
+    },
+    Info {
+        number: 17,
+        title: "Clumsy Crucible",
+        part1: true,
+        part2: true,
+        create: create::<day17::Day17>,
+    },
+    Info {
+        number: 18,
+        title: "Lavaduct Lagoon",
+        part1: true,
+        part2: true,
+        create: create::<day18::Day18>,
+    },
+    Info {
+        number: 19,
+        title: "Aplenty",
+        part1: true,
+        part2: true,
+        create: create::<day19::Day19>,
+    },
+    Info {
+        number: 20,
+        title: "Pulse Propagation",
+        part1: true,
+        part2: true,
+        create: create::<day20::Day20>,
+    },
+    Info {
+        number: 21,
+        title: "Step Counter",
+        part1: true,
+        part2: true,
+        create: create::<day21::Day21>,
+    },
+    Info {
+        number: 22,
+        title: "Sand Slabs",
+        part1: true,
+        part2: true,
+        create: create::<day22::Day22>,
+    },
+    Info {
+        number: 23,
+        title: "A Long Walk",
+        part1: true,
+        part2: true,
+        create: create::<day23::Day23>,
+    },
+    Info {
+        number: 24,
+        title: "Never Tell Me The Odds",
+        part1: true,
+        part2: true,
+        create: create::<day24::Day24>,
+    },
+    Info {
+        number: 25,
+        title: "Snowverload",
+        part1: true,
+        part2: false,
+        create: create::<day25::Day25>,
+    },
+];
+
+pub fn registry() -> impl Iterator<Item = &'static Info> {
+    REGISTRY.iter()
+}
+
+pub fn info(day: u8) -> Result<&'static Info> {
+    registry()
+        .find(|i| i.number == day)
+        .ok_or_else(|| eyre!("Invalid day: {day}"))
+}
+
+pub fn get(day: u8) -> Result<Box<dyn Day>> {
+    Ok(info(day)?.create())
+}
@@ -10,11 +10,11 @@ impl From<&str> for Line {
 }
 
 impl Line {
-    fn digits(&self) -> DigitIter {
+    fn digits(&self) -> DigitIter<'_> {
         DigitIter { values: &self.0 }
     }
 
-    fn digits_and_words(&self) -> DigitAndWordIter {
+    fn digits_and_words(&self) -> DigitAndWordIter<'_> {
         DigitAndWordIter { values: &self.0 }
     }
 
@@ -99,12 +99,8 @@ pub(crate) struct Day01 {
 }
 
 impl Day for Day01 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        self.values = crate::input::Input::get(1)?
-            .data
-            .lines()
-            .map(|s| s.into())
-            .collect();
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        self.values = input.lines().map(|s| s.into()).collect();
 
         Ok(())
     }
@@ -127,3 +123,33 @@ impl Day for Day01 {
             .to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(s: &str) -> Vec<u8> {
+        Line::from(s).digits_and_words().collect()
+    }
+
+    #[test]
+    fn overlapping_words() {
+        assert_eq!(words("eightwo"), [8, 2]);
+        assert_eq!(words("oneight"), [1, 8]);
+        assert_eq!(words("twone3"), [2, 1, 3]);
+        assert_eq!(words("sevenine"), [7, 9]);
+    }
+
+    #[test]
+    fn overlapping_calibration() {
+        assert_eq!(Line::from("eightwo").calibration2(), 82);
+        assert_eq!(Line::from("xtwone3four").calibration2(), 24);
+        assert_eq!(Line::from("7pqrstsixteen").calibration2(), 76);
+    }
+
+    #[test]
+    fn digits_ignore_words() {
+        assert_eq!(Line::from("treb7uchet").calibration(), 77);
+        assert_eq!(Line::from("one2three").digits().collect::<Vec<_>>(), [2]);
+    }
+}
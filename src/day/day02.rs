@@ -82,10 +82,9 @@ pub(crate) struct Day02 {
 }
 
 impl Day for Day02 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(2)?;
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
         self.games = separated(1.., parse_game, newline)
-            .parse(input.data.trim())
+            .parse(input.trim())
             .map_err(|e| eyre!(e.to_string()))?;
 
         Ok(())
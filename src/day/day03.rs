@@ -67,9 +67,8 @@ pub(crate) struct Day03 {
 }
 
 impl Day for Day03 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(3)?;
-        self.map = input.data.as_str().into();
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        self.map = input.into();
 
         Ok(())
     }
@@ -150,8 +149,8 @@ impl Day for Day03 {
         }
 
         Ok(gears
-            .iter()
-            .filter_map(|(_, v)| {
+            .values()
+            .filter_map(|v| {
                 if v.len() == 2 {
                     Some(v.iter().map(|(_, n)| *n).product::<u32>())
                 } else {
@@ -45,10 +45,9 @@ pub(crate) struct Day04 {
 }
 
 impl Day for Day04 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(4)?;
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
         self.cards = separated(1.., parse_card, newline)
-            .parse(input.data.trim())
+            .parse(input.trim())
             .map_err(|e| eyre!(e.to_string()))?;
 
         Ok(())
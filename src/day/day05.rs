@@ -1,9 +1,47 @@
+use std::ops::Range;
+
 use rangemap::RangeMap;
-use rayon::prelude::*;
 
 use super::Day;
 
-type InnerMap = RangeMap<usize, usize>;
+/// One layer of the almanac, as the offset added to each source range. Numbers outside every
+/// source range map to themselves.
+type InnerMap = RangeMap<usize, isize>;
+
+fn shift(range: &Range<usize>, offset: isize) -> Range<usize> {
+    range.start.wrapping_add_signed(offset)..range.end.wrapping_add_signed(offset)
+}
+
+fn intersect(a: &Range<usize>, b: &Range<usize>) -> Option<Range<usize>> {
+    let range = a.start.max(b.start)..a.end.min(b.end);
+    (!range.is_empty()).then_some(range)
+}
+
+/// Maps every number in `ranges` through `map`, splitting the ranges where the mapping changes.
+fn forward(map: &InnerMap, ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+    ranges
+        .iter()
+        .flat_map(|r| {
+            map.overlapping(r)
+                .filter_map(|(k, &offset)| intersect(k, r).map(|i| shift(&i, offset)))
+                .chain(map.gaps(r))
+        })
+        .collect()
+}
+
+/// Finds every number that `map` sends into `ranges`, the inverse of [`forward`].
+fn backward(map: &InnerMap, ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+    ranges
+        .iter()
+        .flat_map(|r| {
+            map.iter()
+                .filter_map(|(k, &offset)| {
+                    intersect(&shift(k, offset), r).map(|i| shift(&i, -offset))
+                })
+                .chain(map.gaps(r))
+        })
+        .collect()
+}
 
 #[derive(Debug, Default)]
 struct Almanac {
@@ -33,7 +71,7 @@ impl From<&str> for Almanac {
                             parts.next().unwrap(),
                             parts.next().unwrap(),
                         );
-                        (source..(source + count), dest)
+                        (source..(source + count), dest as isize - source as isize)
                     })
                     .collect()
             })
@@ -47,13 +85,45 @@ impl Almanac {
     fn location(&self, seed: usize) -> usize {
         let mut current = seed;
         for map in self.maps.iter() {
-            if let Some((k, v)) = map.get_key_value(&current) {
-                current = v + current - k.start;
+            if let Some(&offset) = map.get(&current) {
+                current = current.wrapping_add_signed(offset);
             }
         }
 
         current
     }
+
+    /// Reads the seed numbers as pairs of start and length, as part 2 does.
+    fn seed_ranges(&self) -> Vec<Range<usize>> {
+        self.seeds
+            .chunks_exact(2)
+            .map(|v| v[0]..(v[0] + v[1]))
+            .collect()
+    }
+
+    /// The lowest location reached from any of the seed ranges.
+    fn lowest_location(&self) -> usize {
+        self.locations(self.seed_ranges())
+            .iter()
+            .map(|r| r.start)
+            .min()
+            .unwrap()
+    }
+
+    /// Maps whole ranges of seeds to the ranges of locations they end up in.
+    fn locations(&self, seeds: Vec<Range<usize>>) -> Vec<Range<usize>> {
+        self.maps
+            .iter()
+            .fold(seeds, |ranges, map| forward(map, &ranges))
+    }
+
+    /// Finds the ranges of seeds that end up in a range of locations.
+    fn seeds_for(&self, locations: Range<usize>) -> Vec<Range<usize>> {
+        self.maps
+            .iter()
+            .rev()
+            .fold(vec![locations], |ranges, map| backward(map, &ranges))
+    }
 }
 
 #[derive(Debug, Default)]
@@ -62,9 +132,8 @@ pub(crate) struct Day05 {
 }
 
 impl Day for Day05 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(5)?;
-        self.almanac = input.data.as_str().into();
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        self.almanac = input.into();
 
         Ok(())
     }
@@ -81,20 +150,43 @@ impl Day for Day05 {
     }
 
     fn part2(&self) -> color_eyre::eyre::Result<String> {
-        Ok(self
-            .almanac
-            .seeds
-            .chunks_exact(2)
-            .flat_map(|v| {
-                let range = v[0]..(v[0] + v[1]);
-                let min = range
-                    .into_par_iter()
-                    .map(|s| self.almanac.location(s))
-                    .min();
-                dbg!(min)
-            })
-            .min()
-            .unwrap()
-            .to_string())
+        Ok(self.almanac.lowest_location().to_string())
+    }
+
+    fn report(&self) -> Option<String> {
+        let lowest = self.almanac.lowest_location();
+        let seeds = self.almanac.seed_ranges();
+
+        let mut report = format!("Lowest location: {lowest}\nReached from seeds:\n");
+        for found in self.almanac.seeds_for(lowest..lowest + 1) {
+            for range in &seeds {
+                if let Some(seed) = intersect(&found, range) {
+                    report += &format!("  {seed:?}\n");
+                }
+            }
+        }
+
+        Some(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intervals_round_trip() {
+        let almanac = Almanac::from(
+            "seeds: 79 14 55 13\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\n\
+             soil-to-fertilizer map:\n0 15 37\n37 52 2\n39 0 15",
+        );
+
+        let fertilizer = almanac.locations(vec![0..50, 50..100]);
+        assert_eq!(fertilizer.iter().map(|r| r.len()).sum::<usize>(), 100);
+
+        let seeds = almanac.seeds_for(almanac.location(79)..almanac.location(79) + 1);
+        assert_eq!(seeds.len(), 1);
+        assert_eq!(seeds[0], 79..80);
+        assert!(almanac.seeds_for(53..54).contains(&(14..15)));
     }
 }
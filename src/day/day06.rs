@@ -29,9 +29,8 @@ fn calculate_range(total_time: usize, distance: usize) -> usize {
 }
 
 impl Day for Day06 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(6)?;
-        let mut data = input.data.lines().map(|s| {
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        let mut data = input.lines().map(|s| {
             s.split_whitespace()
                 .skip(1)
                 .map(|s| s.parse().unwrap())
@@ -45,7 +44,6 @@ impl Day for Day06 {
             .zip(data.next().unwrap())
             .collect();
         let [single_time, single_distance] = input
-            .data
             .replace("Time:", "")
             .replace("Distance:", "")
             .replace(' ', "")
@@ -176,10 +176,9 @@ fn parse_line(i: &mut &str) -> PResult<(Hand, usize)> {
 }
 
 impl Day for Day07 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(7)?;
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
         self.hands = separated(1.., parse_line, newline)
-            .parse(input.data.trim())
+            .parse(input.trim())
             .map_err(|e| eyre!(e.to_string()))?;
 
         Ok(())
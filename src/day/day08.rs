@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
 use color_eyre::eyre::eyre;
+use itertools::Itertools;
+use num::integer::{ExtendedGcd, Integer};
 use winnow::ascii::{newline, space1};
 use winnow::combinator::{alt, delimited, repeat, separated, separated_pair};
 use winnow::token::take;
@@ -60,65 +62,236 @@ fn parse_nodes(i: &mut &str) -> PResult<Nodes> {
 }
 
 impl Day for Day08 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(8)?;
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
         (self.directions, self.nodes) =
             separated_pair(parse_directions, (newline, newline), parse_nodes)
-                .parse(input.data.trim())
+                .parse(input.trim())
                 .map_err(|e| eyre!(e.to_string()))?;
 
         Ok(())
     }
 
     fn part1(&self) -> color_eyre::eyre::Result<String> {
-        let mut current = &Name(['A', 'A', 'A']);
+        let mut current = Name(['A', 'A', 'A']);
         let mut direction = self.directions.iter().cycle();
         let mut count = 0;
 
-        while *current != Name(['Z', 'Z', 'Z']) {
+        while current != Name(['Z', 'Z', 'Z']) {
             count += 1;
-            let node = self.nodes.get(current).unwrap();
-            match direction.next().unwrap() {
-                Direction::Left => current = &node.0,
-                Direction::Right => current = &node.1,
-            }
+            current = self.step(current, *direction.next().unwrap())?;
         }
 
         Ok(count.to_string())
     }
 
     fn part2(&self) -> color_eyre::eyre::Result<String> {
-        let mut current: Vec<_> = self
+        let orbits = self.orbits()?;
+        let steps = meeting_point(&orbits)?;
+
+        Ok(steps.to_string())
+    }
+
+    fn report(&self) -> Option<String> {
+        let orbits = self.orbits().ok()?;
+
+        Some(
+            orbits
+                .iter()
+                .map(|(start, o)| {
+                    format!(
+                        "{}: lead-in {}, cycle {}, ends at {:?} then {:?} + {}k\n",
+                        start.0.iter().collect::<String>(),
+                        o.lead_in,
+                        o.length,
+                        o.early,
+                        o.offsets
+                            .iter()
+                            .map(|off| o.lead_in + off)
+                            .collect::<Vec<_>>(),
+                        o.length
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Day08 {
+    fn step(&self, name: Name, direction: Direction) -> color_eyre::eyre::Result<Name> {
+        let node = self
             .nodes
-            .iter()
-            .filter_map(|(k, _)| (k.0[2] == 'A').then_some(k))
-            .collect();
-        let mut direction = self.directions.iter().cycle();
-        let mut count = vec![0u64; current.len()];
-
-        let mut should_loop = true;
-        while should_loop {
-            let d = direction.next().unwrap();
-            should_loop = false;
-            for (i, n) in current
-                .iter_mut()
-                .enumerate()
-                .filter(|(_, n)| n.0[2] != 'Z')
-            {
-                count[i] += 1;
-                should_loop = true;
-                let node = self.nodes.get(n).unwrap();
-                match d {
-                    Direction::Left => *n = &node.0,
-                    Direction::Right => *n = &node.1,
-                }
+            .get(&name)
+            .ok_or_else(|| eyre!("Unknown node: {}", name.0.iter().collect::<String>()))?;
+
+        Ok(match direction {
+            Direction::Left => node.0,
+            Direction::Right => node.1,
+        })
+    }
+
+    /// Follows a ghost from `start` until its (node, instruction) state repeats.
+    fn analyse(&self, start: Name) -> color_eyre::eyre::Result<Orbit> {
+        let mut seen = HashMap::new();
+        let mut ends = Vec::new();
+        let mut current = start;
+
+        for step in 0.. {
+            let instruction = step % self.directions.len();
+            if let Some(first) = seen.insert((current, instruction), step) {
+                let (early, late) = ends.iter().partition(|&&end| end < first);
+                return Ok(Orbit {
+                    lead_in: first,
+                    length: step - first,
+                    early,
+                    offsets: late.into_iter().map(|end| end - first).collect(),
+                });
+            }
+
+            if current.0[2] == 'Z' {
+                ends.push(step);
             }
+            current = self.step(current, self.directions[instruction])?;
         }
 
-        Ok(count
+        unreachable!()
+    }
+
+    /// Analyses every ghost, in order of their starting node.
+    fn orbits(&self) -> color_eyre::eyre::Result<Vec<(Name, Orbit)>> {
+        let mut starts: Vec<_> = self.nodes.keys().filter(|k| k.0[2] == 'A').collect();
+        starts.sort();
+
+        starts
             .into_iter()
-            .reduce(num::integer::lcm)
-            .unwrap()
-            .to_string())
+            .map(|&start| Ok((start, self.analyse(start)?)))
+            .collect()
+    }
+}
+
+/// The steps at which one ghost stands on an end node.
+///
+/// After `lead_in` steps the ghost repeats the same `length` steps forever. It is on an end node
+/// at the steps in `early`, which all come before `lead_in`, and at `lead_in + offset + k * length`
+/// for every offset in `offsets` and every `k >= 0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Orbit {
+    lead_in: usize,
+    length: usize,
+    early: Vec<usize>,
+    offsets: Vec<usize>,
+}
+
+impl Orbit {
+    fn is_at_end(&self, step: usize) -> bool {
+        if step < self.lead_in {
+            self.early.contains(&step)
+        } else {
+            self.offsets
+                .contains(&((step - self.lead_in) % self.length))
+        }
+    }
+}
+
+/// Solves `x = a1 (mod m1)` and `x = a2 (mod m2)` together, for moduli that need not be coprime.
+///
+/// Returns the combined residue and modulus, or `None` if the two never agree.
+fn crt((a1, m1): (i128, i128), (a2, m2): (i128, i128)) -> Option<(i128, i128)> {
+    let ExtendedGcd { gcd, x, .. } = m1.extended_gcd(&m2);
+    if (a2 - a1) % gcd != 0 {
+        return None;
+    }
+
+    let lcm = m1 / gcd * m2;
+    let k = ((a2 - a1) / gcd * x).rem_euclid(m2 / gcd);
+
+    Some(((a1 + m1 * k).rem_euclid(lcm), lcm))
+}
+
+/// The first step at which every ghost stands on an end node at once.
+fn meeting_point(orbits: &[(Name, Orbit)]) -> color_eyre::eyre::Result<usize> {
+    let name = |n: &Name| n.0.iter().collect::<String>();
+    if let Some((start, _)) = orbits
+        .iter()
+        .find(|(_, o)| o.early.is_empty() && o.offsets.is_empty())
+    {
+        return Err(eyre!(
+            "The ghost starting at {} never reaches an end node",
+            name(start)
+        ));
+    }
+
+    // Before the longest lead-in the ghost with that lead-in can only be at its early ends.
+    let Some((_, slowest)) = orbits.iter().max_by_key(|(_, o)| o.lead_in) else {
+        return Err(eyre!("There are no ghosts"));
+    };
+    if let Some(step) = slowest
+        .early
+        .iter()
+        .copied()
+        .find(|&step| orbits.iter().all(|(_, o)| o.is_at_end(step)))
+    {
+        return Ok(step);
+    }
+
+    // After that every ghost is in its cycle, so try every combination of cycle offsets.
+    let settled = slowest.lead_in as i128;
+    let mut best: Option<i128> = None;
+    let combinations = orbits
+        .iter()
+        .map(|(_, o)| {
+            o.offsets
+                .iter()
+                .map(|&off| (((o.lead_in + off) % o.length) as i128, o.length as i128))
+        })
+        .multi_cartesian_product();
+
+    for congruences in combinations {
+        let Some((residue, modulus)) = congruences.into_iter().try_fold((0, 1), crt) else {
+            continue;
+        };
+
+        let rounds = Integer::div_ceil(&(settled - residue).max(0), &modulus);
+        let step = residue + rounds * modulus;
+        best = Some(best.map_or(step, |b| b.min(step)));
+    }
+
+    best.map(|step| step as usize).ok_or_else(|| {
+        eyre!("The ghosts' cycles never line up, so they are never all on end nodes together")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generalised_crt() {
+        assert_eq!(crt((2, 4), (4, 6)), Some((10, 12)));
+        assert_eq!(crt((1, 4), (2, 6)), None);
+        assert_eq!(crt((0, 1), (3, 5)), Some((3, 5)));
+    }
+
+    #[test]
+    fn lead_in_and_offsets() {
+        let orbit = |lead_in, length, early: &[usize], offsets: &[usize]| Orbit {
+            lead_in,
+            length,
+            early: early.to_vec(),
+            offsets: offsets.to_vec(),
+        };
+        let name = Name(['A', 'A', 'A']);
+
+        // Not a clean multiple: ends at 3, 8, 13, ... and 4, 10, 16, ...
+        let a = orbit(1, 5, &[], &[2]);
+        let b = orbit(4, 6, &[], &[0]);
+        assert_eq!(meeting_point(&[(name, a.clone()), (name, b)]).unwrap(), 28);
+
+        // Early ends come first when they line up.
+        let c = orbit(10, 4, &[3], &[1]);
+        assert_eq!(meeting_point(&[(name, a.clone()), (name, c)]).unwrap(), 3);
+
+        let never = orbit(0, 2, &[], &[1]);
+        let even = orbit(0, 2, &[], &[0]);
+        assert!(meeting_point(&[(name, never), (name, even)]).is_err());
     }
 }
@@ -37,10 +37,8 @@ pub(crate) struct Day09 {
 }
 
 impl Day for Day09 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(9)?;
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
         self.sequences = input
-            .data
             .trim()
             .lines()
             .map(|l| {
@@ -1,7 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use color_eyre::eyre::{eyre, Report};
 
+use super::polygon::interior_points;
 use super::Day;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -73,25 +74,42 @@ impl Tile {
     fn has_north_connection(&self) -> bool {
         use Tile::*;
 
-        matches!(self, Vertical | NW | NE | Start)
+        matches!(self, Vertical | NW | NE)
     }
 
     fn has_south_connection(&self) -> bool {
         use Tile::*;
 
-        matches!(self, Vertical | SW | SE | Start)
+        matches!(self, Vertical | SW | SE)
     }
 
     fn has_east_connection(&self) -> bool {
         use Tile::*;
 
-        matches!(self, Horizontal | NE | SE | Start)
+        matches!(self, Horizontal | NE | SE)
     }
 
     fn has_west_connection(&self) -> bool {
         use Tile::*;
 
-        matches!(self, Horizontal | NW | SW | Start)
+        matches!(self, Horizontal | NW | SW)
+    }
+}
+
+impl std::fmt::Display for Tile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Tile::*;
+        let c = match self {
+            Vertical => '│',
+            Horizontal => '─',
+            NE => '└',
+            NW => '┘',
+            SW => '┐',
+            SE => '┌',
+            Ground => '.',
+            Start => 'S',
+        };
+        write!(f, "{c}")
     }
 }
 
@@ -123,103 +141,157 @@ pub(crate) struct Day10 {
     start: Option<Point>,
 }
 
+impl Day10 {
+    /// The pipes that could be hidden under the start, connecting to two neighbours that connect
+    /// back to it.
+    fn start_candidates(&self, start: Point) -> Vec<Tile> {
+        use Tile::*;
+
+        [Vertical, Horizontal, NE, NW, SW, SE]
+            .into_iter()
+            .filter(|tile| tile.connections(&self.tiles, start).len() == 2)
+            .collect()
+    }
+
+    /// Links every tile to the neighbours it shares a connection with.
+    fn connect(&mut self) {
+        self.map = self
+            .tiles
+            .iter()
+            .map(|(&position, tile)| (position, tile.connections(&self.tiles, position)))
+            .collect();
+    }
+
+    /// The tiles of the loop through the start, in order.
+    fn pipe_loop(&self) -> color_eyre::eyre::Result<Vec<Point>> {
+        let start = self.start.ok_or_else(|| eyre!("No start tile"))?;
+
+        let mut path = vec![start];
+        let mut previous = start;
+        let mut current = self.map[&start][0];
+        while current != start {
+            let next = self.map[&current]
+                .iter()
+                .copied()
+                .find(|&p| p != previous)
+                .ok_or_else(|| eyre!("The loop is broken at {current:?}"))?;
+            path.push(current);
+            (previous, current) = (current, next);
+        }
+
+        Ok(path)
+    }
+
+    /// Whether each tile in a row is enclosed by the loop, scanning left to right and flipping at
+    /// every loop tile that connects north.
+    fn enclosed_row(&self, y: isize, width: isize, on_loop: &HashSet<Point>) -> Vec<bool> {
+        let mut inside = false;
+        (0..width)
+            .map(|x| {
+                let p = Point(x, y);
+                if on_loop.contains(&p) {
+                    inside ^= self.tiles[&p].has_north_connection();
+                    false
+                } else {
+                    inside
+                }
+            })
+            .collect()
+    }
+}
+
 impl Day for Day10 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(10)?;
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
         self.tiles = input
-            .data
             .lines()
             .enumerate()
             .flat_map(|(y, s)| {
                 s.char_indices()
-                    .map(move |(x, c)| (Point(x as isize, y as isize), Tile::try_from(c).unwrap()))
+                    .map(move |(x, c)| Ok((Point(x as isize, y as isize), Tile::try_from(c)?)))
             })
-            .collect();
-
-        for (&position, &tile) in self.tiles.iter() {
-            self.map
-                .insert(position, tile.connections(&self.tiles, position));
-
-            if tile == Tile::Start {
-                self.start = Some(position);
+            .collect::<color_eyre::eyre::Result<_>>()?;
+
+        let start = self
+            .tiles
+            .iter()
+            .find_map(|(&p, &t)| (t == Tile::Start).then_some(p))
+            .ok_or_else(|| eyre!("No start tile"))?;
+        self.start = Some(start);
+
+        // More than two neighbours may point into the start, so keep the pipe that closes the loop.
+        for tile in self.start_candidates(start) {
+            self.tiles.insert(start, tile);
+            self.connect();
+            if self.pipe_loop().is_ok() {
+                return Ok(());
             }
         }
 
-        Ok(())
+        Err(eyre!("No pipe under the start closes the loop"))
     }
 
     fn part1(&self) -> color_eyre::eyre::Result<String> {
-        let start = self.start.unwrap();
-        let mut seen = HashMap::new();
-        let mut to_check = vec![start];
-
-        let mut steps = 0;
-        loop {
-            let checking: Vec<_> = to_check
-                .iter()
-                .filter(|&p| !seen.contains_key(p))
-                .copied()
-                .collect();
+        Ok((self.pipe_loop()?.len() / 2).to_string())
+    }
 
-            if checking.is_empty() {
-                break;
-            }
+    fn part2(&self) -> color_eyre::eyre::Result<String> {
+        let vertices: Vec<_> = self
+            .pipe_loop()?
+            .into_iter()
+            .map(|Point(x, y)| (x as i64, y as i64))
+            .collect();
 
-            to_check.clear();
+        Ok(interior_points(&vertices).to_string())
+    }
 
-            for p in checking {
-                seen.insert(p, steps);
+    fn visualize(&self) -> Option<String> {
+        let on_loop: HashSet<_> = self.pipe_loop().ok()?.into_iter().collect();
+        let width = self.tiles.keys().map(|p| p.0 + 1).max()?;
+        let height = self.tiles.keys().map(|p| p.1 + 1).max()?;
 
-                if let Some(connections) = self.map.get(&p) {
-                    to_check.extend(connections.iter());
+        let mut output = String::new();
+        for y in 0..height {
+            let enclosed = self.enclosed_row(y, width, &on_loop);
+            for x in 0..width {
+                let p = Point(x, y);
+                if on_loop.contains(&p) {
+                    output += &self.tiles[&p].to_string();
+                } else if enclosed[x as usize] {
+                    output.push('I');
+                } else {
+                    output.push('O');
                 }
             }
-
-            steps += 1;
+            output.push('\n');
         }
 
-        Ok((steps - 1).to_string())
+        Some(output)
     }
+}
 
-    fn part2(&self) -> color_eyre::eyre::Result<String> {
-        let start = self.start.unwrap();
-        let mut seen = HashMap::new();
-        let mut to_check = vec![start];
-
-        let mut steps = 0;
-        while let Some(p) = to_check.pop() {
-            if seen.contains_key(&p) {
-                continue;
-            }
-
-            seen.insert(p, steps);
-
-            if let Some(connections) = self.map.get(&p) {
-                to_check.extend(connections.iter());
-            }
-
-            steps += 1;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infers_start() {
+        let mut day = Day10::default();
+        day.setup(".....\n.S-7.\n.|.|.\n.L-J.\n.....\n").unwrap();
+
+        assert_eq!(day.tiles[&Point(1, 1)], Tile::SE);
+        assert_eq!(day.pipe_loop().unwrap().len(), 8);
+        assert_eq!(
+            day.visualize().unwrap(),
+            "OOOOO\nO┌─┐O\nO│I│O\nO└─┘O\nOOOOO\n"
+        );
+    }
 
-        let mut fill = 0;
-        let mut winding = 0;
-        for y in 0..200 {
-            for x in 0..200 {
-                let p = Point(x, y);
-                if let Some(n) = seen.get(&p) {
-                    if let Some(m) = seen.get(&p.down()) {
-                        if (steps + n - m) % steps == 1 {
-                            winding += 1;
-                        } else if (steps + m - n) % steps == 1 {
-                            winding -= 1;
-                        }
-                    }
-                } else if winding > 0 {
-                    fill += 1;
-                }
-            }
-        }
+    #[test]
+    fn infers_start_among_extra_neighbours() {
+        let mut day = Day10::default();
+        day.setup("..|..\n.-S-7\n..|.|\n..L-J\n").unwrap();
 
-        Ok(fill.to_string())
+        assert_eq!(day.tiles[&Point(2, 1)], Tile::SE);
+        assert_eq!(day.pipe_loop().unwrap().len(), 8);
     }
 }
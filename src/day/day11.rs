@@ -1,93 +1,177 @@
-use std::collections::HashSet;
-
 use color_eyre::eyre::eyre;
 
 use super::Day;
 
+/// Above this many galaxies, distances are summed over sorted coordinates instead of per pair.
+const PAIRWISE_LIMIT: usize = 2000;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-struct Point(isize, isize);
+struct Point(usize, usize);
 
 #[derive(Debug, Default)]
 pub(crate) struct Day11 {
-    empty_columns: HashSet<isize>,
-    empty_rows: HashSet<isize>,
+    /// The number of empty columns before each column, with one extra entry for the full width.
+    empty_columns: Vec<usize>,
+    /// The number of empty rows before each row, with one extra entry for the full height.
+    empty_rows: Vec<usize>,
     galaxies: Vec<Point>,
 }
 
+/// Running totals of `empty`, starting at zero.
+fn prefix_sums(empty: impl Iterator<Item = bool>) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(empty.scan(0, |total, e| {
+            *total += e as usize;
+            Some(*total)
+        }))
+        .collect()
+}
+
+/// The sum of the distances between every pair of `values`, which must be sorted.
+fn sorted_pair_sum(values: &[usize]) -> usize {
+    let mut before = 0;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| {
+            let sum = v * i - before;
+            before += v;
+            sum
+        })
+        .sum()
+}
+
+impl Day11 {
+    /// Where a galaxy ends up once every empty row and column is `factor` times as wide, for a
+    /// `factor` of at least 1.
+    fn expand(&self, Point(x, y): Point, factor: usize) -> Point {
+        Point(
+            x + self.empty_columns[x] * (factor - 1),
+            y + self.empty_rows[y] * (factor - 1),
+        )
+    }
+
+    /// Sums the distances between every pair of galaxies, one pair at a time.
+    fn pairwise_distance(&self, factor: usize) -> usize {
+        let expanded: Vec<_> = self
+            .galaxies
+            .iter()
+            .map(|&g| self.expand(g, factor))
+            .collect();
+
+        expanded
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| expanded[i + 1..].iter().map(move |b| (a, b)))
+            .map(|(Point(x1, y1), Point(x2, y2))| x1.abs_diff(*x2) + y1.abs_diff(*y2))
+            .sum()
+    }
+
+    /// Sums the distances between every pair of galaxies in `O(n log n)`, by summing the
+    /// differences along each axis separately over sorted coordinates.
+    fn sorted_distance(&self, factor: usize) -> usize {
+        let (mut xs, mut ys): (Vec<_>, Vec<_>) = self
+            .galaxies
+            .iter()
+            .map(|&g| {
+                let Point(x, y) = self.expand(g, factor);
+                (x, y)
+            })
+            .unzip();
+        xs.sort_unstable();
+        ys.sort_unstable();
+
+        sorted_pair_sum(&xs) + sorted_pair_sum(&ys)
+    }
+
+    fn total_distance(&self, factor: usize) -> color_eyre::eyre::Result<usize> {
+        if factor == 0 {
+            return Err(eyre!("The expansion factor must be at least 1"));
+        }
+
+        Ok(if self.galaxies.len() > PAIRWISE_LIMIT {
+            self.sorted_distance(factor)
+        } else {
+            self.pairwise_distance(factor)
+        })
+    }
+}
+
 impl Day for Day11 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(11)?;
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        let mut width = 0;
+        let mut rows = Vec::new();
+        let mut galaxies = Vec::new();
 
-        let mut max_y = 0;
-        let mut column_galaxies = HashSet::new();
-        for (y, s) in input.data.lines().enumerate() {
-            let y = y as isize;
+        for (y, s) in input.lines().enumerate() {
             let mut has_row_galaxy = false;
 
             for (x, c) in s.char_indices() {
-                let x = x as isize;
                 match c {
                     '.' => {}
                     '#' => {
-                        self.galaxies.push(Point(x, y));
-                        column_galaxies.insert(x);
+                        galaxies.push(Point(x, y));
                         has_row_galaxy = true;
-                        max_y = max_y.max(y);
                     }
                     _ => return Err(eyre!("Invalid character at ({x}, {y}): {c}")),
                 }
             }
 
-            if !has_row_galaxy {
-                self.empty_rows.insert(y);
-            }
+            width = width.max(s.len());
+            rows.push(!has_row_galaxy);
         }
 
-        self.empty_columns = (0..=max_y)
-            .filter(|y| !column_galaxies.contains(y))
-            .collect();
+        let mut columns = vec![true; width];
+        for &Point(x, _) in &galaxies {
+            columns[x] = false;
+        }
+
+        self.empty_columns = prefix_sums(columns.into_iter());
+        self.empty_rows = prefix_sums(rows.into_iter());
+        self.galaxies = galaxies;
 
         Ok(())
     }
 
     fn part1(&self) -> color_eyre::eyre::Result<String> {
-        let mut sum = 0;
-
-        for (i, &Point(x1, y1)) in self.galaxies.iter().enumerate() {
-            for &Point(x2, y2) in self.galaxies.iter().skip(i + 1) {
-                let dist = x2.abs_diff(x1) + y2.abs_diff(y1);
-                let empty_columns = (x1.min(x2)..=x1.max(x2))
-                    .filter(|x| self.empty_columns.contains(x))
-                    .count();
-                let empty_rows = (y1.min(y2)..=y1.max(y2))
-                    .filter(|y| self.empty_rows.contains(y))
-                    .count();
-
-                sum += (dist - empty_rows - empty_columns) + (2 * (empty_rows + empty_columns));
-            }
-        }
-
-        Ok(sum.to_string())
+        Ok(self.total_distance(2)?.to_string())
     }
 
     fn part2(&self) -> color_eyre::eyre::Result<String> {
-        let mut sum = 0;
-
-        for (i, &Point(x1, y1)) in self.galaxies.iter().enumerate() {
-            for &Point(x2, y2) in self.galaxies.iter().skip(i + 1) {
-                let dist = x2.abs_diff(x1) + y2.abs_diff(y1);
-                let empty_columns = (x1.min(x2)..=x1.max(x2))
-                    .filter(|x| self.empty_columns.contains(x))
-                    .count();
-                let empty_rows = (y1.min(y2)..=y1.max(y2))
-                    .filter(|y| self.empty_rows.contains(y))
-                    .count();
-
-                sum += (dist - empty_rows - empty_columns)
-                    + (1_000_000 * (empty_rows + empty_columns));
-            }
+        Ok(self.total_distance(1_000_000)?.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expansion_factors() {
+        let mut day = Day11::default();
+        day.setup(
+            "...#......\n.......#..\n#.........\n..........\n......#...\n\
+             .#........\n.........#\n..........\n.......#..\n#...#.....\n",
+        )
+        .unwrap();
+
+        for (factor, expected) in [(2, 374), (10, 1030), (100, 8410)] {
+            assert_eq!(day.pairwise_distance(factor), expected);
+            assert_eq!(day.sorted_distance(factor), expected);
         }
+    }
+
+    #[test]
+    fn wide_image() {
+        let mut day = Day11::default();
+        day.setup("#....#\n......\n").unwrap();
+
+        assert_eq!(day.empty_columns, [0, 0, 1, 2, 3, 4, 4]);
+        assert_eq!(day.total_distance(2).unwrap(), 9);
 
-        Ok(sum.to_string())
+        day.setup("#....#\n......\n").unwrap();
+        assert_eq!(day.total_distance(2).unwrap(), 9);
+        assert_eq!(day.total_distance(1).unwrap(), 5);
+        assert!(day.total_distance(0).is_err());
     }
 }
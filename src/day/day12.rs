@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use color_eyre::eyre::eyre;
 use rayon::prelude::*;
 use winnow::ascii::{digit1, newline, space1};
@@ -29,6 +31,65 @@ struct PartialSequence {
 }
 
 impl PartialSequence {
+    /// Repeats the row `copies` times, joining the springs with unknowns.
+    fn unfold(&self, copies: usize) -> Self {
+        let mut springs = Vec::with_capacity((self.springs.len() + 1) * copies);
+        for i in 0..copies {
+            if i > 0 {
+                springs.push(Spring::Unknown);
+            }
+            springs.extend_from_slice(&self.springs);
+        }
+
+        Self {
+            springs,
+            records: self.records.repeat(copies),
+        }
+    }
+
+    /// Counts the arrangements that match the records without listing them.
+    fn arrangements(&self) -> usize {
+        self.count(0, 0, 0, &mut HashMap::new())
+    }
+
+    /// Counts the arrangements of `springs[spring..]` that match `records[record..]`, where the
+    /// last `run` springs before `spring` were damaged.
+    fn count(
+        &self,
+        spring: usize,
+        record: usize,
+        run: usize,
+        memo: &mut HashMap<(usize, usize, usize), usize>,
+    ) -> usize {
+        let Some(&current) = self.springs.get(spring) else {
+            let finished = match run {
+                0 => record == self.records.len(),
+                run => record + 1 == self.records.len() && self.records[record] == run,
+            };
+            return finished as usize;
+        };
+
+        if let Some(&count) = memo.get(&(spring, record, run)) {
+            return count;
+        }
+
+        let mut count = 0;
+        if current != Spring::Operational && self.records.get(record).is_some_and(|&r| run < r) {
+            count += self.count(spring + 1, record, run + 1, memo);
+        }
+        if current != Spring::Damaged {
+            if run == 0 {
+                count += self.count(spring + 1, record, 0, memo);
+            } else if self.records[record] == run {
+                count += self.count(spring + 1, record + 1, 0, memo);
+            }
+        }
+
+        memo.insert((spring, record, run), count);
+        count
+    }
+
+    /// Lists every arrangement by brute force, which is only feasible for short rows.
     fn possible_sequences(&self) -> PossibleSequences<'_> {
         let unknown_count = self
             .springs
@@ -109,16 +170,18 @@ impl<'a> Iterator for PossibleSequences<'a> {
     }
 }
 
+/// Rows with more unknowns than this are too slow to enumerate in the report.
+const ENUMERATION_LIMIT: usize = 16;
+
 #[derive(Debug, Default)]
 pub(crate) struct Day12 {
     groups: Vec<PartialSequence>,
 }
 
 impl Day for Day12 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(12)?;
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
         self.groups = separated(1.., parse_partial, newline)
-            .parse(input.data.trim())
+            .parse(input.trim())
             .map_err(|e| eyre!(e.to_string()))?;
 
         Ok(())
@@ -128,12 +191,76 @@ impl Day for Day12 {
         Ok(self
             .groups
             .par_iter()
-            .map(|g| g.possible_sequences().count())
+            .map(|g| g.arrangements())
             .sum::<usize>()
             .to_string())
     }
 
     fn part2(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        Ok(self
+            .groups
+            .par_iter()
+            .map(|g| g.unfold(5).arrangements())
+            .sum::<usize>()
+            .to_string())
+    }
+
+    fn report(&self) -> Option<String> {
+        let checked: Vec<_> = self
+            .groups
+            .par_iter()
+            .enumerate()
+            .filter(|(_, g)| {
+                g.springs.iter().filter(|&&s| s == Spring::Unknown).count() <= ENUMERATION_LIMIT
+            })
+            .map(|(row, g)| (row, g.arrangements(), g.possible_sequences().count()))
+            .collect();
+
+        let mut report = format!(
+            "Checked {} of {} rows against enumeration\n",
+            checked.len(),
+            self.groups.len()
+        );
+        for (row, counted, enumerated) in checked {
+            if counted != enumerated {
+                report += &format!(
+                    "Row {}: counted {counted}, enumerated {enumerated}\n",
+                    row + 1
+                );
+            }
+        }
+
+        Some(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_enumeration() {
+        for row in [
+            "???.### 1,1,3",
+            "?###???????? 3,2,1",
+            "??#?.??? 1,1",
+            "#.#?? 1,1",
+        ] {
+            let partial = parse_partial.parse(row).unwrap();
+            assert_eq!(
+                partial.arrangements(),
+                partial.possible_sequences().count(),
+                "{row}"
+            );
+        }
+    }
+
+    #[test]
+    fn unfold() {
+        let partial = parse_partial.parse(".# 1").unwrap().unfold(3);
+
+        assert_eq!(partial.records, [1, 1, 1]);
+        assert_eq!(partial.springs.len(), 8);
+        assert_eq!(partial.springs[2], Spring::Unknown);
     }
 }
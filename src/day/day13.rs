@@ -86,10 +86,8 @@ pub(crate) struct Day13 {
 }
 
 impl Day for Day13 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(13)?;
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
         self.maps = input
-            .data
             .trim()
             .split("\n\n")
             .map(|b| {
@@ -1,13 +1,25 @@
+use std::collections::HashMap;
+
 use super::Day;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+const SPIN_CYCLES: usize = 1_000_000_000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 enum Tile {
     Empty,
     Round,
     Cube,
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Direction {
+    North,
+    West,
+    South,
+    East,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 struct Grid {
     stride: usize,
     height: usize,
@@ -20,61 +32,45 @@ pub(crate) struct Day14 {
 }
 
 impl Grid {
-    fn index(&self, x: usize, y: usize) -> usize {
-        y * self.stride + x
-    }
-
-    fn get(&self, x: usize, y: usize) -> Tile {
-        self.data[self.index(x, y)]
-    }
-
-    fn swap(&mut self, x1: usize, y1: usize, x2: usize, y2: usize) {
-        let index1 = self.index(x1, y1);
-        let index2 = self.index(x2, y2);
-        self.data.swap(index1, index2);
-    }
-
-    fn shift_up(&mut self) {
-        for y in 1..self.height {
-            for x in 0..self.stride {
-                match (self.get(x, y), self.get(x, y - 1)) {
-                    (Tile::Round, Tile::Empty) => self.swap(x, y, x, y - 1),
-                    _ => {}
-                }
-            }
-        }
-    }
-
-    fn shift_right(&mut self) {
-        for y in 0..self.height {
-            for x in 0..(self.stride - 1) {
-                match (self.get(x, y), self.get(x + 1, y)) {
-                    (Tile::Round, Tile::Empty) => self.swap(x, y, x + 1, y),
-                    _ => {}
-                }
-            }
-        }
-    }
+    /// Rolls every round rock as far as it goes in `direction`, in a single pass per line.
+    fn tilt(&mut self, direction: Direction) {
+        let (stride, height) = (self.stride, self.height);
+        let (lines, length) = match direction {
+            Direction::North | Direction::South => (stride, height),
+            Direction::West | Direction::East => (height, stride),
+        };
 
-    fn shift_down(&mut self) {
-        for y in 0..(self.height - 1) {
-            for x in 0..self.stride {
-                match (self.get(x, y), self.get(x, y + 1)) {
-                    (Tile::Round, Tile::Empty) => self.swap(x, y, x, y + 1),
-                    _ => {}
+        for line in 0..lines {
+            // The k-th tile of the line, counting from the edge the rocks roll towards.
+            let index = |k: usize| match direction {
+                Direction::North => k * stride + line,
+                Direction::South => (height - 1 - k) * stride + line,
+                Direction::West => line * stride + k,
+                Direction::East => line * stride + stride - 1 - k,
+            };
+
+            let mut free = 0;
+            for k in 0..length {
+                match self.data[index(k)] {
+                    Tile::Cube => free = k + 1,
+                    Tile::Round => {
+                        self.data.swap(index(k), index(free));
+                        free += 1;
+                    }
+                    Tile::Empty => {}
                 }
             }
         }
     }
 
-    fn shift_left(&mut self) {
-        for y in 0..self.height {
-            for x in 1..self.stride {
-                match (self.get(x, y), self.get(x - 1, y)) {
-                    (Tile::Round, Tile::Empty) => self.swap(x, y, x - 1, y),
-                    _ => {}
-                }
-            }
+    fn spin_cycle(&mut self) {
+        for direction in [
+            Direction::North,
+            Direction::West,
+            Direction::South,
+            Direction::East,
+        ] {
+            self.tilt(direction);
         }
     }
 
@@ -106,11 +102,9 @@ impl std::fmt::Display for Grid {
 }
 
 impl Day for Day14 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(14)?;
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
         let (stride, data) =
             input
-                .data
                 .char_indices()
                 .fold((None, Vec::new()), |(mut stride, mut data), (i, c)| {
                     match c {
@@ -132,52 +126,30 @@ impl Day for Day14 {
     }
 
     fn part1(&self) -> color_eyre::eyre::Result<String> {
-        let mut old_grid = self.grid.clone();
         let mut grid = self.grid.clone();
-
-        loop {
-            grid.shift_up();
-            if old_grid == grid {
-                break;
-            } else {
-                old_grid = grid.clone();
-            }
-        }
+        grid.tilt(Direction::North);
 
         Ok(grid.total_load().to_string())
     }
 
     fn part2(&self) -> color_eyre::eyre::Result<String> {
         let mut grid = self.grid.clone();
-
-        let direction = [
-            Grid::shift_up,
-            Grid::shift_left,
-            Grid::shift_down,
-            Grid::shift_right,
-        ];
-
-        let cycle = |grid: &mut Grid| {
-            for func in &direction {
-                let mut old_grid = grid.clone();
-                loop {
-                    func(grid);
-
-                    if old_grid == *grid {
-                        break;
-                    } else {
-                        old_grid = grid.clone();
-                    }
+        let mut seen = HashMap::new();
+
+        // The grid settles into a loop quickly, so skip every whole lap of it.
+        let mut cycle = 0;
+        while cycle < SPIN_CYCLES {
+            if let Some(start) = seen.insert(grid.clone(), cycle) {
+                let length = cycle - start;
+                cycle += (SPIN_CYCLES - cycle) / length * length;
+                seen.clear();
+                if cycle == SPIN_CYCLES {
+                    break;
                 }
             }
-        };
 
-        let total = 1000000000;
-        for i in 0..total {
-            if i % 100000 == 0 {
-                println!("{:0.2}%", 100.0 * (i as f64 / total as f64));
-            }
-            cycle(&mut grid);
+            grid.spin_cycle();
+            cycle += 1;
         }
 
         Ok(grid.total_load().to_string())
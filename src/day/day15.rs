@@ -49,11 +49,10 @@ pub(crate) struct Day15 {
 }
 
 impl Day for Day15 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(15)?;
-        self.hash = Hash(input.data.trim().as_bytes().to_vec());
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        self.hash = Hash(input.trim().as_bytes().to_vec());
         self.operations = separated(1.., parse_operation, ',')
-            .parse(input.data.trim())
+            .parse(input.trim())
             .map_err(|e| eyre!(e.to_string()))?;
 
         Ok(())
@@ -108,3 +107,16 @@ impl Day for Day15 {
             .to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash() {
+        assert_eq!(get_hash(b"HASH"), 52);
+        assert_eq!(get_hash(b"rn=1"), 30);
+        assert_eq!(get_hash(b"rn"), 0);
+        assert_eq!(get_hash(b"qp"), 1);
+    }
+}
@@ -205,11 +205,9 @@ impl Day16 {
 }
 
 impl Day for Day16 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        let input = crate::input::Input::get(16)?;
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
         let (stride, data) =
             input
-                .data
                 .char_indices()
                 .fold((None, Vec::new()), |(mut stride, mut data), (i, c)| {
                     match c {
@@ -1,18 +1,127 @@
+use color_eyre::eyre::eyre;
+
+use super::search::{shortest_path, WeightedGrid};
 use super::Day;
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn step(&self, (x, y): (isize, isize)) -> (isize, isize) {
+        match self {
+            Direction::Up => (x, y - 1),
+            Direction::Down => (x, y + 1),
+            Direction::Left => (x - 1, y),
+            Direction::Right => (x + 1, y),
+        }
+    }
+
+    fn turns(&self) -> [Direction; 2] {
+        match self {
+            Direction::Up | Direction::Down => [Direction::Left, Direction::Right],
+            Direction::Left | Direction::Right => [Direction::Up, Direction::Down],
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct State {
+    position: (isize, isize),
+    direction: Direction,
+    run: u8,
+}
+
+/// How many blocks a crucible must and may move in a straight line.
+#[derive(Debug, Copy, Clone)]
+struct Crucible {
+    min_run: u8,
+    max_run: u8,
+}
+
+impl Crucible {
+    const NORMAL: Crucible = Crucible {
+        min_run: 1,
+        max_run: 3,
+    };
+
+    const ULTRA: Crucible = Crucible {
+        min_run: 4,
+        max_run: 10,
+    };
+
+    fn neighbours(&self, grid: &WeightedGrid, state: &State) -> Vec<(State, usize)> {
+        let straight = (state.run < self.max_run).then_some((state.direction, state.run + 1));
+        let turns = state
+            .direction
+            .turns()
+            .into_iter()
+            .filter(|_| state.run >= self.min_run)
+            .map(|d| (d, 1));
+
+        straight
+            .into_iter()
+            .chain(turns)
+            .filter_map(|(direction, run)| {
+                let position = direction.step(state.position);
+                grid.get(position).map(|heat| {
+                    let next = State {
+                        position,
+                        direction,
+                        run,
+                    };
+                    (next, heat)
+                })
+            })
+            .collect()
+    }
+
+    fn minimum_heat_loss(&self, grid: &WeightedGrid) -> Option<usize> {
+        let goal = grid.bottom_right();
+        let starts = [Direction::Right, Direction::Down].map(|direction| State {
+            position: (0, 0),
+            direction,
+            run: 0,
+        });
+
+        shortest_path(
+            starts,
+            |s| self.neighbours(grid, s),
+            // A run of 0 is only possible at the start, which needs no moves on a 1×1 grid.
+            |s| s.position == goal && (s.run == 0 || s.run >= self.min_run),
+            |s| goal.0.abs_diff(s.position.0) + goal.1.abs_diff(s.position.1),
+        )
+        .map(|(heat, _)| heat)
+    }
+}
+
 #[derive(Debug, Default)]
-pub(crate) struct Day17 {}
+pub(crate) struct Day17 {
+    grid: WeightedGrid,
+}
 
 impl Day for Day17 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        todo!()
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        self.grid = input.try_into()?;
+
+        Ok(())
     }
 
     fn part1(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        Ok(Crucible::NORMAL
+            .minimum_heat_loss(&self.grid)
+            .ok_or_else(|| eyre!("No path to the factory"))?
+            .to_string())
     }
 
     fn part2(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        Ok(Crucible::ULTRA
+            .minimum_heat_loss(&self.grid)
+            .ok_or_else(|| eyre!("No path to the factory"))?
+            .to_string())
     }
 }
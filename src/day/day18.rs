@@ -1,18 +1,110 @@
+use color_eyre::eyre::eyre;
+use winnow::ascii::{digit1, newline, space1};
+use winnow::combinator::{alt, delimited, separated, separated_pair};
+use winnow::token::take;
+use winnow::{PResult, Parser};
+
+use super::polygon::covered_points;
 use super::Day;
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+fn parse_direction(i: &mut &str) -> PResult<Direction> {
+    alt((
+        'U'.value(Direction::Up),
+        'D'.value(Direction::Down),
+        'L'.value(Direction::Left),
+        'R'.value(Direction::Right),
+    ))
+    .parse_next(i)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Instruction {
+    direction: Direction,
+    distance: i64,
+}
+
+/// The colour field is really five hex digits of distance followed by a direction digit.
+fn parse_colour(i: &mut &str) -> PResult<Instruction> {
+    delimited(
+        "(#",
+        (
+            take(5usize).try_map(|s| i64::from_str_radix(s, 16)),
+            alt((
+                '0'.value(Direction::Right),
+                '1'.value(Direction::Down),
+                '2'.value(Direction::Left),
+                '3'.value(Direction::Up),
+            )),
+        ),
+        ')',
+    )
+    .map(|(distance, direction)| Instruction {
+        direction,
+        distance,
+    })
+    .parse_next(i)
+}
+
+fn parse_line(i: &mut &str) -> PResult<(Instruction, Instruction)> {
+    separated_pair(
+        separated_pair(parse_direction, space1, digit1.parse_to()).map(|(direction, distance)| {
+            Instruction {
+                direction,
+                distance,
+            }
+        }),
+        space1,
+        parse_colour,
+    )
+    .parse_next(i)
+}
+
+fn lagoon_size(instructions: &[Instruction]) -> i64 {
+    let vertices: Vec<_> = instructions
+        .iter()
+        .scan((0, 0), |(x, y), i| {
+            match i.direction {
+                Direction::Up => *y -= i.distance,
+                Direction::Down => *y += i.distance,
+                Direction::Left => *x -= i.distance,
+                Direction::Right => *x += i.distance,
+            }
+            Some((*x, *y))
+        })
+        .collect();
+
+    covered_points(&vertices)
+}
+
 #[derive(Debug, Default)]
-pub(crate) struct Day18 {}
+pub(crate) struct Day18 {
+    instructions: Vec<Instruction>,
+    colour_instructions: Vec<Instruction>,
+}
 
 impl Day for Day18 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        todo!()
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        let lines: Vec<_> = separated(1.., parse_line, newline)
+            .parse(input.trim())
+            .map_err(|e| eyre!(e.to_string()))?;
+        (self.instructions, self.colour_instructions) = lines.into_iter().unzip();
+
+        Ok(())
     }
 
     fn part1(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        Ok(lagoon_size(&self.instructions).to_string())
     }
 
     fn part2(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        Ok(lagoon_size(&self.colour_instructions).to_string())
     }
 }
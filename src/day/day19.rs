@@ -1,18 +1,311 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use color_eyre::eyre::eyre;
+use rangemap::RangeSet;
+use winnow::ascii::{alpha1, digit1, newline};
+use winnow::combinator::{alt, delimited, opt, separated, separated_pair, terminated};
+use winnow::{PResult, Parser};
+
 use super::Day;
 
+/// Every rating lies in `1..=4000`.
+const RATINGS: Range<u64> = 1..4001;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Category {
+    X,
+    M,
+    A,
+    S,
+}
+
+impl Category {
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+fn parse_category(i: &mut &str) -> PResult<Category> {
+    alt((
+        'x'.value(Category::X),
+        'm'.value(Category::M),
+        'a'.value(Category::A),
+        's'.value(Category::S),
+    ))
+    .parse_next(i)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    Greater,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Condition {
+    category: Category,
+    comparison: Comparison,
+    value: u64,
+}
+
+impl Condition {
+    /// The ratings of `category` that satisfy the condition.
+    fn range(&self) -> Range<u64> {
+        match self.comparison {
+            Comparison::Less => 0..self.value,
+            Comparison::Greater => self.value + 1..u64::MAX,
+        }
+    }
+
+    fn matches(&self, part: &Part) -> bool {
+        self.range().contains(&part.0[self.category.index()])
+    }
+}
+
+fn parse_condition(i: &mut &str) -> PResult<Condition> {
+    (
+        parse_category,
+        alt(('<'.value(Comparison::Less), '>'.value(Comparison::Greater))),
+        digit1.parse_to(),
+    )
+        .map(|(category, comparison, value)| Condition {
+            category,
+            comparison,
+            value,
+        })
+        .parse_next(i)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Accept,
+    Reject,
+    Workflow(String),
+}
+
+fn parse_target(i: &mut &str) -> PResult<Target> {
+    alpha1
+        .map(|name| match name {
+            "A" => Target::Accept,
+            "R" => Target::Reject,
+            name => Target::Workflow(name.to_string()),
+        })
+        .parse_next(i)
+}
+
+/// A rule without a condition always applies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    condition: Option<Condition>,
+    target: Target,
+}
+
+fn parse_rule(i: &mut &str) -> PResult<Rule> {
+    (opt(terminated(parse_condition, ':')), parse_target)
+        .map(|(condition, target)| Rule { condition, target })
+        .parse_next(i)
+}
+
+type Workflow = (String, Vec<Rule>);
+
+fn parse_workflow(i: &mut &str) -> PResult<Workflow> {
+    (
+        alpha1.map(str::to_string),
+        delimited('{', separated(1.., parse_rule, ','), '}'),
+    )
+        .parse_next(i)
+}
+
+/// Ratings indexed by [`Category::index`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Part([u64; 4]);
+
+impl Part {
+    fn total(&self) -> u64 {
+        self.0.iter().sum()
+    }
+}
+
+fn parse_part(i: &mut &str) -> PResult<Part> {
+    delimited(
+        "{x=",
+        (
+            digit1.parse_to(),
+            ",m=",
+            digit1.parse_to(),
+            ",a=",
+            digit1.parse_to(),
+            ",s=",
+            digit1.parse_to(),
+        ),
+        '}',
+    )
+    .map(|(x, _, m, _, a, _, s)| Part([x, m, a, s]))
+    .parse_next(i)
+}
+
+fn parse_input(i: &mut &str) -> PResult<(Vec<Workflow>, Vec<Part>)> {
+    separated_pair(
+        separated(1.., parse_workflow, newline),
+        (newline, newline),
+        separated(1.., parse_part, newline),
+    )
+    .parse_next(i)
+}
+
+/// A box of parts, one set of ratings per category.
+type Hyperrectangle = [RangeSet<u64>; 4];
+
+fn volume(parts: &Hyperrectangle) -> u64 {
+    parts
+        .iter()
+        .map(|set| set.iter().map(|r| r.end - r.start).sum::<u64>())
+        .product()
+}
+
+/// Splits `parts` into the ones that satisfy `condition` and the ones that do not.
+fn split(parts: &Hyperrectangle, condition: &Condition) -> (Hyperrectangle, Hyperrectangle) {
+    let index = condition.category.index();
+    let range = condition.range();
+
+    let mut matching = parts.clone();
+    let mut rest = parts.clone();
+
+    matching[index] = RangeSet::new();
+    if range.is_empty() {
+        return (matching, rest);
+    }
+
+    for r in parts[index].overlapping(&range) {
+        matching[index].insert(r.start.max(range.start)..r.end.min(range.end));
+    }
+    rest[index].remove(range);
+
+    (matching, rest)
+}
+
 #[derive(Debug, Default)]
-pub(crate) struct Day19 {}
+pub(crate) struct Day19 {
+    workflows: HashMap<String, Vec<Rule>>,
+    parts: Vec<Part>,
+}
+
+impl Day19 {
+    fn workflow(&self, name: &str) -> color_eyre::eyre::Result<&[Rule]> {
+        self.workflows
+            .get(name)
+            .map(Vec::as_slice)
+            .ok_or_else(|| eyre!("Unknown workflow: {name}"))
+    }
+
+    fn accepts(&self, part: &Part) -> color_eyre::eyre::Result<bool> {
+        let mut name = "in";
+
+        // Without a cycle a part can visit every workflow at most once.
+        for _ in 0..=self.workflows.len() {
+            let rule = self
+                .workflow(name)?
+                .iter()
+                .find(|r| r.condition.is_none_or(|c| c.matches(part)))
+                .ok_or_else(|| eyre!("No rule applies in workflow {name}"))?;
+
+            match &rule.target {
+                Target::Accept => return Ok(true),
+                Target::Reject => return Ok(false),
+                Target::Workflow(next) => name = next,
+            }
+        }
+
+        Err(eyre!("Workflows loop for {part:?}"))
+    }
+
+    /// Counts the parts in `parts` that end up accepted when starting from workflow `name`.
+    fn count_accepted(
+        &self,
+        name: &str,
+        mut parts: Hyperrectangle,
+        depth: usize,
+    ) -> color_eyre::eyre::Result<u64> {
+        if depth > self.workflows.len() {
+            return Err(eyre!("Workflows loop through {name}"));
+        }
+
+        let mut accepted = 0;
+        for rule in self.workflow(name)? {
+            let matching = match &rule.condition {
+                Some(condition) => {
+                    let (matching, rest) = split(&parts, condition);
+                    parts = rest;
+                    matching
+                }
+                None => std::mem::take(&mut parts),
+            };
+
+            if volume(&matching) > 0 {
+                accepted += match &rule.target {
+                    Target::Accept => volume(&matching),
+                    Target::Reject => 0,
+                    Target::Workflow(next) => self.count_accepted(next, matching, depth + 1)?,
+                };
+            }
+
+            if volume(&parts) == 0 {
+                break;
+            }
+        }
+
+        Ok(accepted)
+    }
+}
 
 impl Day for Day19 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        todo!()
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        let (workflows, parts) = parse_input
+            .parse(input.trim())
+            .map_err(|e| eyre!(e.to_string()))?;
+
+        self.workflows = workflows.into_iter().collect();
+        self.parts = parts;
+
+        Ok(())
     }
 
     fn part1(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        let mut total = 0;
+        for part in &self.parts {
+            if self.accepts(part)? {
+                total += part.total();
+            }
+        }
+
+        Ok(total.to_string())
     }
 
     fn part2(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        let all = std::array::from_fn(|_| RangeSet::from_iter([RATINGS]));
+
+        Ok(self.count_accepted("in", all, 0)?.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_on_condition() {
+        let all: Hyperrectangle = std::array::from_fn(|_| RangeSet::from_iter([RATINGS]));
+        let condition = parse_condition.parse("m>2090").unwrap();
+
+        let (matching, rest) = split(&all, &condition);
+
+        assert_eq!(volume(&matching), 1910 * 4000 * 4000 * 4000);
+        assert_eq!(volume(&rest), 2090 * 4000 * 4000 * 4000);
+        assert_eq!(volume(&split(&rest, &condition).0), 0);
+
+        let (matching, rest) = split(&all, &parse_condition.parse("x<0").unwrap());
+        assert_eq!(volume(&matching), 0);
+        assert_eq!(volume(&rest), 4000 * 4000 * 4000 * 4000);
     }
 }
@@ -1,18 +1,296 @@
+use std::collections::{HashMap, VecDeque};
+
+use color_eyre::eyre::eyre;
+use winnow::ascii::{alpha1, newline};
+use winnow::combinator::{alt, opt, separated, separated_pair};
+use winnow::{PResult, Parser};
+
 use super::Day;
 
+/// The number of button presses traced by the report.
+const TRACED_PRESSES: usize = 4;
+
+/// Give up on finding the `rx` cycles after this many button presses.
+const MAX_PRESSES: usize = 100_000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Kind {
+    Broadcaster,
+    FlipFlop,
+    Conjunction,
+    /// Named as a destination but never defined, like `output` or `rx`, or the button itself.
+    Sink,
+}
+
+/// A module's type prefix and name, followed by the names of its destinations.
+type Definition<'a> = ((Option<char>, &'a str), Vec<&'a str>);
+
+fn parse_module<'a>(i: &mut &'a str) -> PResult<Definition<'a>> {
+    separated_pair(
+        (opt(alt(('%', '&'))), alpha1),
+        " -> ",
+        separated(1.., alpha1, ", "),
+    )
+    .parse_next(i)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Pulse {
+    from: usize,
+    to: usize,
+    high: bool,
+}
+
+/// The wiring of the modules, without any state.
+#[derive(Debug, Default, Clone)]
+struct Network {
+    names: Vec<String>,
+    kinds: Vec<Kind>,
+    outputs: Vec<Vec<usize>>,
+    inputs: Vec<Vec<usize>>,
+    button: usize,
+    broadcaster: usize,
+}
+
+impl Network {
+    fn index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    /// Formats a pulse the way the puzzle does, e.g. `broadcaster -low-> a`.
+    fn describe(&self, pulse: &Pulse) -> String {
+        let level = if pulse.high { "high" } else { "low" };
+        format!(
+            "{} -{level}-> {}",
+            self.names[pulse.from], self.names[pulse.to]
+        )
+    }
+}
+
+impl TryFrom<&str> for Network {
+    type Error = color_eyre::Report;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let modules: Vec<_> = separated(1.., parse_module, newline)
+            .parse(value.trim())
+            .map_err(|e| eyre!(e.to_string()))?;
+
+        let mut network = Network::default();
+        let mut indices = HashMap::new();
+        let mut index = |network: &mut Network, name: &str| {
+            *indices.entry(name.to_string()).or_insert_with(|| {
+                network.names.push(name.to_string());
+                network.kinds.push(Kind::Sink);
+                network.outputs.push(Vec::new());
+                network.inputs.push(Vec::new());
+                network.names.len() - 1
+            })
+        };
+
+        network.button = index(&mut network, "button");
+        for ((prefix, name), _) in &modules {
+            let i = index(&mut network, name);
+            network.kinds[i] = match (prefix, *name) {
+                (Some('%'), _) => Kind::FlipFlop,
+                (Some('&'), _) => Kind::Conjunction,
+                (None, "broadcaster") => Kind::Broadcaster,
+                _ => return Err(eyre!("Module {name} has no type")),
+            };
+        }
+
+        for ((_, name), outputs) in &modules {
+            let from = index(&mut network, name);
+            for output in outputs {
+                let to = index(&mut network, output);
+                network.outputs[from].push(to);
+                network.inputs[to].push(from);
+            }
+        }
+
+        network.broadcaster = network
+            .index("broadcaster")
+            .ok_or_else(|| eyre!("No broadcaster module"))?;
+
+        Ok(network)
+    }
+}
+
+/// The state of every module, advanced one button press at a time.
+#[derive(Debug, Clone)]
+struct Simulator<'a> {
+    network: &'a Network,
+    on: Vec<bool>,
+    /// The last pulse remembered by each conjunction, in the order of `Network::inputs`.
+    memory: Vec<Vec<bool>>,
+}
+
+impl<'a> Simulator<'a> {
+    fn new(network: &'a Network) -> Self {
+        Self {
+            network,
+            on: vec![false; network.names.len()],
+            memory: network
+                .inputs
+                .iter()
+                .map(|inputs| vec![false; inputs.len()])
+                .collect(),
+        }
+    }
+
+    /// Presses the button and delivers every resulting pulse in order, calling `observe` with
+    /// each one as it is delivered.
+    fn press_with(&mut self, mut observe: impl FnMut(&Pulse)) {
+        let network = self.network;
+        let mut queue = VecDeque::from([Pulse {
+            from: network.button,
+            to: network.broadcaster,
+            high: false,
+        }]);
+
+        while let Some(pulse) = queue.pop_front() {
+            observe(&pulse);
+
+            let to = pulse.to;
+            let high = match network.kinds[to] {
+                Kind::Broadcaster => pulse.high,
+                Kind::FlipFlop if pulse.high => continue,
+                Kind::FlipFlop => {
+                    self.on[to] = !self.on[to];
+                    self.on[to]
+                }
+                Kind::Conjunction => {
+                    let slot = network.inputs[to]
+                        .iter()
+                        .position(|&i| i == pulse.from)
+                        .unwrap();
+                    self.memory[to][slot] = pulse.high;
+                    !self.memory[to].iter().all(|&h| h)
+                }
+                Kind::Sink => continue,
+            };
+
+            queue.extend(network.outputs[to].iter().map(|&next| Pulse {
+                from: to,
+                to: next,
+                high,
+            }));
+        }
+    }
+
+    /// Presses the button, returning the number of low and high pulses sent.
+    fn press(&mut self) -> (usize, usize) {
+        let (mut low, mut high) = (0, 0);
+        self.press_with(|p| {
+            if p.high {
+                high += 1;
+            } else {
+                low += 1;
+            }
+        });
+
+        (low, high)
+    }
+
+    /// Presses the button, returning the pulses sent in the format used by the puzzle.
+    fn trace(&mut self) -> Vec<String> {
+        let network = self.network;
+        let mut trace = Vec::new();
+        self.press_with(|p| trace.push(network.describe(p)));
+
+        trace
+    }
+}
+
 #[derive(Debug, Default)]
-pub(crate) struct Day20 {}
+pub(crate) struct Day20 {
+    network: Network,
+}
 
 impl Day for Day20 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        todo!()
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        self.network = Network::try_from(input)?;
+
+        Ok(())
     }
 
     fn part1(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        let mut simulator = Simulator::new(&self.network);
+        let (low, high) = (0..1000)
+            .map(|_| simulator.press())
+            .fold((0, 0), |(l, h), (dl, dh)| (l + dl, h + dh));
+
+        Ok((low * high).to_string())
     }
 
     fn part2(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        let network = &self.network;
+        let rx = network.index("rx").ok_or_else(|| eyre!("No rx module"))?;
+
+        // `rx` is fed by a single conjunction, which only sends it a low pulse once all of its own
+        // inputs last sent it a high pulse. Each of those inputs does so on a fixed cycle.
+        let [feeder] = network.inputs[rx][..] else {
+            return Err(eyre!("rx must have exactly one input"));
+        };
+        if network.kinds[feeder] != Kind::Conjunction {
+            return Err(eyre!("rx must be fed by a conjunction"));
+        }
+
+        let sources = &network.inputs[feeder];
+        let mut cycles = vec![None; sources.len()];
+        let mut simulator = Simulator::new(network);
+
+        for presses in 1..=MAX_PRESSES {
+            simulator.press_with(|p| {
+                if p.to == feeder && p.high {
+                    let slot = sources.iter().position(|&s| s == p.from).unwrap();
+                    cycles[slot].get_or_insert(presses);
+                }
+            });
+
+            if cycles.iter().all(Option::is_some) {
+                let presses = cycles.into_iter().flatten().reduce(num::integer::lcm);
+                return Ok(presses.unwrap().to_string());
+            }
+        }
+
+        Err(eyre!("No cycle found within {MAX_PRESSES} presses"))
+    }
+
+    fn report(&self) -> Option<String> {
+        let mut simulator = Simulator::new(&self.network);
+
+        Some(
+            (1..=TRACED_PRESSES)
+                .map(|press| format!("Press {press}:\n{}\n", simulator.trace().join("\n")))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace() {
+        let network = Network::try_from(
+            "broadcaster -> a\n%a -> inv, con\n&inv -> b\n%b -> con\n&con -> output\n",
+        )
+        .unwrap();
+        let mut simulator = Simulator::new(&network);
+
+        assert_eq!(simulator.trace().len(), 8);
+        assert_eq!(
+            simulator.trace(),
+            [
+                "button -low-> broadcaster",
+                "broadcaster -low-> a",
+                "a -low-> inv",
+                "a -low-> con",
+                "inv -high-> b",
+                "con -high-> output",
+            ]
+        );
     }
 }
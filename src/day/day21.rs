@@ -1,18 +1,200 @@
+use std::collections::{HashSet, VecDeque};
+
+use color_eyre::eyre::{eyre, Report};
+
 use super::Day;
 
+const PART1_STEPS: usize = 64;
+const PART2_STEPS: usize = 26501365;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Garden {
+    stride: usize,
+    height: usize,
+    rocks: Vec<bool>,
+    start: (isize, isize),
+}
+
+impl TryFrom<&str> for Garden {
+    type Error = Report;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut stride = None;
+        let mut rocks = Vec::new();
+        let mut start = None;
+
+        for (y, line) in value.lines().filter(|l| !l.is_empty()).enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '.' => rocks.push(false),
+                    '#' => rocks.push(true),
+                    'S' if start.is_none() => {
+                        start = Some((x as isize, y as isize));
+                        rocks.push(false);
+                    }
+                    'S' => return Err(eyre!("More than one start")),
+                    _ => return Err(eyre!("Invalid tile: {c}")),
+                }
+            }
+
+            if *stride.get_or_insert(line.len()) != line.len() {
+                return Err(eyre!("Ragged garden row: {line}"));
+            }
+        }
+
+        let stride = stride.ok_or_else(|| eyre!("Empty garden"))?;
+        Ok(Self {
+            stride,
+            height: rocks.len() / stride,
+            rocks,
+            start: start.ok_or_else(|| eyre!("No start"))?,
+        })
+    }
+}
+
+impl Garden {
+    /// Whether `(x, y)` is a garden plot. Outside the map the garden either ends or, when
+    /// `infinite`, repeats in every direction.
+    fn is_plot(&self, (x, y): (isize, isize), infinite: bool) -> bool {
+        let (w, h) = (self.stride as isize, self.height as isize);
+        let inside = (0..w).contains(&x) && (0..h).contains(&y);
+        if !inside && !infinite {
+            return false;
+        }
+
+        !self.rocks[y.rem_euclid(h) as usize * self.stride + x.rem_euclid(w) as usize]
+    }
+
+    /// The number of plots reachable in exactly `n` steps, for every `n` up to `max_steps`.
+    ///
+    /// A plot at distance `d` can be reached in exactly `n` steps whenever `d <= n` and `d` has
+    /// the same parity as `n`, by stepping back and forth at the end.
+    fn reachable_counts(&self, max_steps: usize, infinite: bool) -> Vec<usize> {
+        let mut at_distance = vec![0; max_steps + 1];
+        let mut seen = HashSet::from([self.start]);
+        let mut queue = VecDeque::from([(self.start, 0)]);
+
+        while let Some(((x, y), distance)) = queue.pop_front() {
+            at_distance[distance] += 1;
+            if distance == max_steps {
+                continue;
+            }
+
+            for next in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                if self.is_plot(next, infinite) && seen.insert(next) {
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        let mut counts = at_distance;
+        for n in 2..counts.len() {
+            counts[n] += counts[n - 2];
+        }
+
+        counts
+    }
+
+    /// Samples the infinite garden at `offset`, `offset + size` and `offset + 2 * size` steps,
+    /// where `offset` is `steps` modulo the garden size.
+    ///
+    /// Real inputs have a clear row and column through the start and a clear border, so the
+    /// reachable area grows by whole copies of the garden every `size` steps, which makes these
+    /// samples a quadratic in the number of copies crossed.
+    fn samples(&self, steps: usize) -> color_eyre::eyre::Result<[usize; 3]> {
+        let size = self.stride;
+        if self.height != size || self.start != ((size / 2) as isize, (size / 2) as isize) {
+            return Err(eyre!(
+                "Extrapolation needs a square garden starting in the middle"
+            ));
+        }
+
+        let offset = steps % size;
+        let counts = self.reachable_counts(offset + 2 * size, true);
+
+        Ok([0, 1, 2].map(|k| counts[offset + k * size]))
+    }
+}
+
+/// Evaluates at `x` the quadratic through `(0, y0)`, `(1, y1)` and `(2, y2)`, using Newton's
+/// forward differences.
+fn extrapolate([y0, y1, y2]: [usize; 3], x: usize) -> usize {
+    let first = y1 - y0;
+    let second = y2 + y0 - 2 * y1;
+
+    y0 + x * first + x * (x.saturating_sub(1)) / 2 * second
+}
+
 #[derive(Debug, Default)]
-pub(crate) struct Day21 {}
+pub(crate) struct Day21 {
+    garden: Garden,
+}
 
 impl Day for Day21 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        todo!()
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        self.garden = Garden::try_from(input)?;
+
+        Ok(())
     }
 
     fn part1(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        let counts = self.garden.reachable_counts(PART1_STEPS, false);
+
+        Ok(counts[PART1_STEPS].to_string())
     }
 
     fn part2(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        let samples = self.garden.samples(PART2_STEPS)?;
+        let copies = PART2_STEPS / self.garden.stride;
+
+        Ok(extrapolate(samples, copies).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........
+";
+
+    #[test]
+    fn bounded() {
+        let garden = Garden::try_from(EXAMPLE).unwrap();
+        assert_eq!(garden.reachable_counts(6, false)[6], 16);
+    }
+
+    #[test]
+    fn infinite() {
+        let garden = Garden::try_from(EXAMPLE).unwrap();
+        let counts = garden.reachable_counts(500, true);
+
+        assert_eq!(counts[6], 16);
+        assert_eq!(counts[10], 50);
+        assert_eq!(counts[50], 1594);
+        assert_eq!(counts[100], 6536);
+        assert_eq!(counts[500], 167004);
+    }
+
+    #[test]
+    fn extrapolation_matches_brute_force() {
+        let garden = Garden::try_from(".....\n.#.#.\n..S..\n.#.#.\n.....\n").unwrap();
+        let counts = garden.reachable_counts(2 + 5 * 6, true);
+
+        let samples = garden.samples(2 + 5 * 6).unwrap();
+        for copies in 0..=6 {
+            assert_eq!(extrapolate(samples, copies), counts[2 + 5 * copies]);
+        }
     }
 }
@@ -1,18 +1,207 @@
+use std::collections::HashMap;
+
+use color_eyre::eyre::eyre;
+use winnow::ascii::{digit1, newline};
+use winnow::combinator::{separated, separated_pair};
+use winnow::{PResult, Parser};
+
 use super::Day;
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Point {
+    x: usize,
+    y: usize,
+    z: usize,
+}
+
+fn parse_point(i: &mut &str) -> PResult<Point> {
+    (
+        digit1.parse_to(),
+        ',',
+        digit1.parse_to(),
+        ',',
+        digit1.parse_to(),
+    )
+        .map(|(x, _, y, _, z)| Point { x, y, z })
+        .parse_next(i)
+}
+
+/// A straight line of cubes between two corners, inclusive.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Brick {
+    start: Point,
+    end: Point,
+}
+
+fn parse_brick(i: &mut &str) -> PResult<Brick> {
+    separated_pair(parse_point, '~', parse_point)
+        .map(|(a, b)| Brick {
+            start: Point {
+                x: a.x.min(b.x),
+                y: a.y.min(b.y),
+                z: a.z.min(b.z),
+            },
+            end: Point {
+                x: a.x.max(b.x),
+                y: a.y.max(b.y),
+                z: a.z.max(b.z),
+            },
+        })
+        .parse_next(i)
+}
+
+impl Brick {
+    fn footprint(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (self.start.x..=self.end.x)
+            .flat_map(move |x| (self.start.y..=self.end.y).map(move |y| (x, y)))
+    }
+
+    fn height(&self) -> usize {
+        self.end.z - self.start.z + 1
+    }
+}
+
+/// The settled pile, as the bricks each brick rests directly on.
+///
+/// Bricks are numbered from the bottom up, so every brick's supports come before it.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Pile {
+    supports: Vec<Vec<usize>>,
+}
+
+impl Pile {
+    fn settle(mut bricks: Vec<Brick>) -> Self {
+        bricks.sort_by_key(|b| b.start.z);
+
+        // The height of the highest cube and the brick it belongs to, for every column.
+        let mut tops: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        let mut supports = Vec::with_capacity(bricks.len());
+
+        for (index, brick) in bricks.iter().enumerate() {
+            let floor = brick
+                .footprint()
+                .filter_map(|c| tops.get(&c))
+                .map(|&(z, _)| z)
+                .max()
+                .unwrap_or(0);
+
+            let mut below: Vec<_> = brick
+                .footprint()
+                .filter_map(|c| tops.get(&c))
+                .filter(|&&(z, _)| z == floor)
+                .map(|&(_, i)| i)
+                .collect();
+            below.sort_unstable();
+            below.dedup();
+            supports.push(below);
+
+            let top = floor + brick.height();
+            for c in brick.footprint() {
+                tops.insert(c, (top, index));
+            }
+        }
+
+        Self { supports }
+    }
+
+    /// Whether removing `brick` leaves everything it holds up still supported.
+    fn is_removable(&self, brick: usize) -> bool {
+        self.supports
+            .iter()
+            .filter(|s| s.contains(&brick))
+            .all(|s| s.len() > 1)
+    }
+
+    /// The immediate dominator of every brick, where `None` is the ground.
+    ///
+    /// Brick `a` dominates brick `b` when every path of supports from `b` down to the ground
+    /// passes through `a`, which is exactly when removing `a` makes `b` fall.
+    fn dominators(&self) -> Vec<Option<usize>> {
+        let mut parent: Vec<Option<usize>> = Vec::with_capacity(self.supports.len());
+        let mut depth: Vec<usize> = Vec::with_capacity(self.supports.len());
+        let depth_of = |depth: &[usize], node: Option<usize>| node.map_or(0, |n| depth[n]);
+
+        for supports in &self.supports {
+            let mut below = supports.iter().map(|&s| Some(s));
+            // Resting on the ground means nothing but the ground dominates it.
+            let mut common = below.next().unwrap_or(None);
+
+            for mut other in below {
+                while common != other {
+                    if depth_of(&depth, common) >= depth_of(&depth, other) {
+                        common = common.and_then(|c| parent[c]);
+                    } else {
+                        other = other.and_then(|o| parent[o]);
+                    }
+                }
+            }
+
+            depth.push(depth_of(&depth, common) + 1);
+            parent.push(common);
+        }
+
+        parent
+    }
+
+    /// The number of other bricks that fall when each brick is removed.
+    fn falls(&self) -> Vec<usize> {
+        let dominators = self.dominators();
+        let mut falls = vec![0; dominators.len()];
+
+        // Children always come after their dominator, so walking backwards sees a whole subtree
+        // before its root.
+        for brick in (0..dominators.len()).rev() {
+            if let Some(parent) = dominators[brick] {
+                falls[parent] += falls[brick] + 1;
+            }
+        }
+
+        falls
+    }
+}
+
 #[derive(Debug, Default)]
-pub(crate) struct Day22 {}
+pub(crate) struct Day22 {
+    pile: Pile,
+}
 
 impl Day for Day22 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        todo!()
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        let bricks = separated(1.., parse_brick, newline)
+            .parse(input.trim())
+            .map_err(|e| eyre!(e.to_string()))?;
+        self.pile = Pile::settle(bricks);
+
+        Ok(())
     }
 
     fn part1(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        let removable = (0..self.pile.supports.len())
+            .filter(|&b| self.pile.is_removable(b))
+            .count();
+
+        Ok(removable.to_string())
     }
 
     fn part2(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        Ok(self.pile.falls().iter().sum::<usize>().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dominated_through_either_support() {
+        // A is held up by B and C, which both rest on D, so only D brings A down.
+        let pile = Pile::settle(
+            separated(1.., parse_brick, newline)
+                .parse("0,0,1~2,0,1\n0,0,2~0,0,2\n2,0,2~2,0,2\n0,0,3~2,0,3")
+                .unwrap(),
+        );
+
+        assert_eq!(pile.dominators(), [None, Some(0), Some(0), Some(0)]);
+        assert_eq!(pile.falls(), [3, 0, 0, 0]);
     }
 }
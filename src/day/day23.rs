@@ -1,18 +1,268 @@
+use std::collections::HashMap;
+
+use color_eyre::eyre::{eyre, Report};
+use rayon::prelude::*;
+
 use super::Day;
 
+/// Search the first few levels of the junction graph in parallel, and the rest sequentially.
+const PARALLEL_DEPTH: usize = 4;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    fn step(self, (x, y): (isize, isize)) -> (isize, isize) {
+        match self {
+            Direction::Up => (x, y - 1),
+            Direction::Down => (x, y + 1),
+            Direction::Left => (x - 1, y),
+            Direction::Right => (x + 1, y),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Tile {
+    Path,
+    Forest,
+    Slope(Direction),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Map {
+    stride: usize,
+    height: usize,
+    data: Vec<Tile>,
+}
+
+impl TryFrom<&str> for Map {
+    type Error = Report;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut stride = None;
+        let mut data = Vec::new();
+
+        for line in value.lines().filter(|l| !l.is_empty()) {
+            for c in line.chars() {
+                data.push(match c {
+                    '.' => Tile::Path,
+                    '#' => Tile::Forest,
+                    '^' => Tile::Slope(Direction::Up),
+                    'v' => Tile::Slope(Direction::Down),
+                    '<' => Tile::Slope(Direction::Left),
+                    '>' => Tile::Slope(Direction::Right),
+                    _ => return Err(eyre!("Invalid tile: {c}")),
+                });
+            }
+
+            if *stride.get_or_insert(line.len()) != line.len() {
+                return Err(eyre!("Ragged map row: {line}"));
+            }
+        }
+
+        let stride = stride.ok_or_else(|| eyre!("Empty map"))?;
+        Ok(Self {
+            stride,
+            height: data.len() / stride,
+            data,
+        })
+    }
+}
+
+impl Map {
+    fn get(&self, (x, y): (isize, isize)) -> Tile {
+        if x < 0 || y < 0 || x as usize >= self.stride || y as usize >= self.height {
+            Tile::Forest
+        } else {
+            self.data[y as usize * self.stride + x as usize]
+        }
+    }
+
+    /// The only path tile in row `y`.
+    fn gap(&self, y: usize) -> color_eyre::eyre::Result<(isize, isize)> {
+        let mut gaps = (0..self.stride as isize)
+            .map(|x| (x, y as isize))
+            .filter(|&p| self.get(p) == Tile::Path);
+
+        match (gaps.next(), gaps.next()) {
+            (Some(gap), None) => Ok(gap),
+            _ => Err(eyre!("Row {y} must have exactly one path tile")),
+        }
+    }
+
+    /// Whether `position` can be entered moving in `direction`.
+    fn can_enter(&self, position: (isize, isize), direction: Direction, slippery: bool) -> bool {
+        match self.get(position) {
+            Tile::Path => true,
+            Tile::Forest => false,
+            Tile::Slope(slope) => !slippery || slope == direction,
+        }
+    }
+
+    fn is_junction(&self, position: (isize, isize)) -> bool {
+        Direction::ALL
+            .iter()
+            .filter(|d| self.get(d.step(position)) != Tile::Forest)
+            .count()
+            > 2
+    }
+}
+
+/// The trails between junctions, with junction 0 the start and junction 1 the end.
+///
+/// Every junction's neighbours are stored as a bitset index and the length of the trail there.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Graph {
+    edges: Vec<Vec<(usize, usize)>>,
+}
+
+impl Graph {
+    fn compress(map: &Map, slippery: bool) -> color_eyre::eyre::Result<Self> {
+        let start = map.gap(0)?;
+        let end = map.gap(map.height - 1)?;
+
+        let mut junctions: HashMap<_, _> = [(start, 0), (end, 1)].into();
+        for y in 0..map.height as isize {
+            for x in 0..map.stride as isize {
+                if map.get((x, y)) != Tile::Forest && map.is_junction((x, y)) {
+                    let next = junctions.len();
+                    junctions.entry((x, y)).or_insert(next);
+                }
+            }
+        }
+
+        if junctions.len() > u64::BITS as usize {
+            return Err(eyre!("Too many junctions: {}", junctions.len()));
+        }
+
+        let mut edges = vec![Vec::new(); junctions.len()];
+        for (&from, &index) in &junctions {
+            for direction in Direction::ALL {
+                if let Some(edge) = Self::follow(map, &junctions, from, direction, slippery) {
+                    edges[index].push(edge);
+                }
+            }
+        }
+
+        Ok(Self { edges })
+    }
+
+    /// Walks from junction `from` in `direction` until the next junction, returning its index
+    /// and the distance, or `None` at a dead end or a slope that cannot be climbed.
+    fn follow(
+        map: &Map,
+        junctions: &HashMap<(isize, isize), usize>,
+        from: (isize, isize),
+        direction: Direction,
+        slippery: bool,
+    ) -> Option<(usize, usize)> {
+        let mut previous = from;
+        let mut position = direction.step(from);
+        if !map.can_enter(position, direction, slippery) {
+            return None;
+        }
+        let mut distance = 1;
+
+        loop {
+            if let Some(&junction) = junctions.get(&position) {
+                return Some((junction, distance));
+            }
+
+            // Between junctions the trail has exactly one way on besides the way back.
+            let (direction, next) = Direction::ALL
+                .into_iter()
+                .map(|d| (d, d.step(position)))
+                .find(|&(_, next)| next != previous && map.get(next) != Tile::Forest)?;
+            if !map.can_enter(next, direction, slippery) {
+                return None;
+            }
+
+            (previous, position) = (position, next);
+            distance += 1;
+        }
+    }
+
+    /// The longest trail from `node` to the end that avoids the junctions in `visited`.
+    fn longest(&self, node: usize, visited: u64, depth: usize) -> Option<usize> {
+        if node == 1 {
+            return Some(0);
+        }
+
+        let visited = visited | 1 << node;
+        let next = |&(to, distance): &(usize, usize)| {
+            (visited & 1 << to == 0)
+                .then(|| self.longest(to, visited, depth + 1))
+                .flatten()
+                .map(|rest| rest + distance)
+        };
+
+        if depth < PARALLEL_DEPTH {
+            self.edges[node].par_iter().filter_map(next).max()
+        } else {
+            self.edges[node].iter().filter_map(next).max()
+        }
+    }
+}
+
 #[derive(Debug, Default)]
-pub(crate) struct Day23 {}
+pub(crate) struct Day23 {
+    map: Map,
+}
+
+impl Day23 {
+    fn longest_hike(&self, slippery: bool) -> color_eyre::eyre::Result<String> {
+        let graph = Graph::compress(&self.map, slippery)?;
+        let longest = graph
+            .longest(0, 0, 0)
+            .ok_or_else(|| eyre!("No way to the end"))?;
+
+        Ok(longest.to_string())
+    }
+}
 
 impl Day for Day23 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        todo!()
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        self.map = Map::try_from(input)?;
+
+        Ok(())
     }
 
     fn part1(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        self.longest_hike(true)
     }
 
     fn part2(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        self.longest_hike(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slopes_are_one_way() {
+        let map = Map::try_from("#.###\n#...#\n#.#v#\n#...#\n###.#\n").unwrap();
+
+        let slippery = Graph::compress(&map, true).unwrap();
+        let dry = Graph::compress(&map, false).unwrap();
+
+        assert_eq!(slippery.longest(0, 0, 0), Some(6));
+        assert_eq!(dry.longest(0, 0, 0), Some(6));
+        assert_eq!(slippery.edges.iter().map(Vec::len).sum::<usize>(), 7);
+        assert_eq!(dry.edges.iter().map(Vec::len).sum::<usize>(), 8);
     }
 }
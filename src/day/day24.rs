@@ -1,18 +1,232 @@
+use color_eyre::eyre::eyre;
+use num::{BigRational, Signed, Zero};
+use winnow::ascii::{dec_int, newline, space0, space1};
+use winnow::combinator::{separated, separated_pair};
+use winnow::{PResult, Parser};
+
 use super::Day;
 
+/// The test area for part 1, on both the x and y axis.
+const TEST_AREA: (i64, i64) = (200000000000000, 400000000000000);
+
+type Vector = [i64; 3];
+
+fn parse_vector(i: &mut &str) -> PResult<Vector> {
+    (dec_int, (',', space1), dec_int, (',', space1), dec_int)
+        .map(|(x, _, y, _, z)| [x, y, z])
+        .parse_next(i)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Hailstone {
+    position: Vector,
+    velocity: Vector,
+}
+
+fn parse_hailstone(i: &mut &str) -> PResult<Hailstone> {
+    separated_pair(parse_vector, (space0, '@', space1), parse_vector)
+        .map(|(position, velocity)| Hailstone { position, velocity })
+        .parse_next(i)
+}
+
+fn rational(n: i64) -> BigRational {
+    BigRational::from_integer(n.into())
+}
+
+impl Hailstone {
+    /// Where the paths of two hailstones cross in the xy plane, ignoring z, as long as both
+    /// get there in the future.
+    fn crossing(&self, other: &Hailstone) -> Option<(BigRational, BigRational)> {
+        let [px, py, _] = self.position.map(rational);
+        let [vx, vy, _] = self.velocity.map(rational);
+        let [qx, qy, _] = other.position.map(rational);
+        let [wx, wy, _] = other.velocity.map(rational);
+
+        let det = &vx * &wy - &vy * &wx;
+        if det.is_zero() {
+            return None;
+        }
+
+        let (dx, dy) = (qx - &px, qy - &py);
+        let t = (&dx * &wy - &dy * &wx) / &det;
+        let s = (&dx * &vy - &dy * &vx) / &det;
+        if t.is_negative() || s.is_negative() {
+            return None;
+        }
+
+        Some((px + &t * vx, py + t * vy))
+    }
+}
+
+fn crossings_within(hailstones: &[Hailstone], (low, high): (i64, i64)) -> usize {
+    let area = rational(low)..=rational(high);
+
+    hailstones
+        .iter()
+        .enumerate()
+        .flat_map(|(i, a)| hailstones[i + 1..].iter().map(move |b| (a, b)))
+        .filter_map(|(a, b)| a.crossing(b))
+        .filter(|(x, y)| area.contains(x) && area.contains(y))
+        .count()
+}
+
+fn cross(a: [BigRational; 3], b: [BigRational; 3]) -> [BigRational; 3] {
+    let [ax, ay, az] = a;
+    let [bx, by, bz] = b;
+    [
+        &ay * &bz - &az * &by,
+        &az * &bx - &ax * &bz,
+        ax * by - ay * bx,
+    ]
+}
+
+/// The rows of `P × w + q × V = c` for unknown `P` and `V`, as coefficients of
+/// `[Px, Py, Pz, Vx, Vy, Vz]` followed by the constant.
+fn cross_rows(
+    w: [BigRational; 3],
+    q: [BigRational; 3],
+    c: [BigRational; 3],
+) -> Vec<Vec<BigRational>> {
+    let zero = BigRational::zero;
+    let [wx, wy, wz] = w;
+    let [qx, qy, qz] = q;
+    let [cx, cy, cz] = c;
+
+    vec![
+        vec![
+            zero(),
+            wz.clone(),
+            -wy.clone(),
+            zero(),
+            -qz.clone(),
+            qy.clone(),
+            cx,
+        ],
+        vec![-wz, zero(), wx.clone(), qz, zero(), -qx.clone(), cy],
+        vec![wy, -wx, zero(), -qy, qx, zero(), cz],
+    ]
+}
+
+/// Solves a square linear system given as an augmented matrix, or returns `None` if it is
+/// singular.
+fn solve(mut rows: Vec<Vec<BigRational>>) -> Option<Vec<BigRational>> {
+    let n = rows.len();
+
+    for column in 0..n {
+        let pivot = (column..n).find(|&r| !rows[r][column].is_zero())?;
+        rows.swap(column, pivot);
+
+        let divisor = rows[column][column].clone();
+        for value in &mut rows[column] {
+            *value /= &divisor;
+        }
+
+        let pivot_row = rows[column].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != column && !row[column].is_zero() {
+                let factor = row[column].clone();
+                for (value, p) in row.iter_mut().zip(&pivot_row).skip(column) {
+                    *value -= &factor * p;
+                }
+            }
+        }
+    }
+
+    Some(rows.into_iter().map(|row| row[n].clone()).collect())
+}
+
+/// Finds the position and velocity of a rock that hits every hailstone.
+///
+/// The rock hits hailstone `i` when `P - p_i` is parallel to `V - v_i`, i.e. when
+/// `(P - p_i) × (V - v_i) = 0`. The only non-linear term is `P × V`, which is the same for every
+/// hailstone, so subtracting the equations of two hailstones leaves three linear equations.
+/// Two such pairs give a system for all six unknowns, solved exactly with rationals.
+fn throw(hailstones: &[Hailstone]) -> Option<([BigRational; 3], [BigRational; 3])> {
+    let equations = |a: &Hailstone, b: &Hailstone| {
+        let [p, v, q, w] =
+            [a.position, a.velocity, b.position, b.velocity].map(|x| x.map(rational));
+
+        let dv = std::array::from_fn(|i| &v[i] - &w[i]);
+        let dp = std::array::from_fn(|i| &p[i] - &q[i]);
+        let [c1, c2] = [cross(p, v), cross(q, w)];
+        let c = std::array::from_fn(|i| &c1[i] - &c2[i]);
+
+        cross_rows(dv, dp, c)
+    };
+
+    let first = hailstones.first()?;
+    for (i, a) in hailstones.iter().enumerate().skip(1) {
+        for b in &hailstones[i + 1..] {
+            let mut rows = equations(first, a);
+            rows.extend(equations(first, b));
+
+            if let Some(solution) = solve(rows) {
+                let [px, py, pz, vx, vy, vz] = solution.try_into().ok()?;
+                return Some(([px, py, pz], [vx, vy, vz]));
+            }
+        }
+    }
+
+    None
+}
+
 #[derive(Debug, Default)]
-pub(crate) struct Day24 {}
+pub(crate) struct Day24 {
+    hailstones: Vec<Hailstone>,
+}
 
 impl Day for Day24 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        todo!()
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        self.hailstones = separated(1.., parse_hailstone, newline)
+            .parse(input.trim())
+            .map_err(|e| eyre!(e.to_string()))?;
+
+        Ok(())
     }
 
     fn part1(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        Ok(crossings_within(&self.hailstones, TEST_AREA).to_string())
     }
 
     fn part2(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        let (position, _) =
+            throw(&self.hailstones).ok_or_else(|| eyre!("No rock hits every hailstone"))?;
+
+        let sum: BigRational = position.into_iter().sum();
+        if !sum.is_integer() {
+            return Err(eyre!("The rock does not start at whole coordinates: {sum}"));
+        }
+
+        Ok(sum.to_integer().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+    fn example() -> Vec<Hailstone> {
+        separated(1.., parse_hailstone, newline)
+            .parse(EXAMPLE)
+            .unwrap()
+    }
+
+    #[test]
+    fn crossings() {
+        assert_eq!(crossings_within(&example(), (7, 27)), 2);
+    }
+
+    #[test]
+    fn rock() {
+        let (position, velocity) = throw(&example()).unwrap();
+        assert_eq!(position, [24, 13, 10].map(rational));
+        assert_eq!(velocity, [-3, 1, 2].map(rational));
     }
 }
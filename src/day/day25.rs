@@ -1,18 +1,70 @@
+use color_eyre::eyre::eyre;
+use winnow::ascii::{alpha1, newline};
+use winnow::combinator::{separated, separated_pair};
+use winnow::{PResult, Parser};
+
+use super::graph::Graph;
 use super::Day;
 
+/// The number of wires that have to be disconnected.
+const WIRES: usize = 3;
+
+fn parse_line<'a>(i: &mut &'a str) -> PResult<(&'a str, Vec<&'a str>)> {
+    separated_pair(alpha1, ": ", separated(1.., alpha1, ' ')).parse_next(i)
+}
+
 #[derive(Debug, Default)]
-pub(crate) struct Day25 {}
+pub(crate) struct Day25 {
+    wiring: Graph,
+}
 
 impl Day for Day25 {
-    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
-        todo!()
+    fn setup(&mut self, input: &str) -> color_eyre::eyre::Result<()> {
+        let lines: Vec<_> = separated(1.., parse_line, newline)
+            .parse(input.trim())
+            .map_err(|e| eyre!(e.to_string()))?;
+
+        let mut wiring = Graph::default();
+        for (component, connected) in lines {
+            let a = wiring.vertex(component);
+            for other in connected {
+                let b = wiring.vertex(other);
+                wiring.add_edge(a, b, 1);
+            }
+        }
+        self.wiring = wiring;
+
+        Ok(())
     }
 
     fn part1(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        let cut = self
+            .wiring
+            .min_cut()
+            .ok_or_else(|| eyre!("Need at least two components"))?;
+        if cut.weight != WIRES {
+            return Err(eyre!(
+                "The smallest cut is {} wires instead of {WIRES}",
+                cut.weight
+            ));
+        }
+
+        let group = cut.side.len();
+        Ok((group * (self.wiring.len() - group)).to_string())
     }
 
     fn part2(&self) -> color_eyre::eyre::Result<String> {
-        todo!()
+        Err(eyre!("Day 25 has no second part"))
+    }
+
+    fn report(&self) -> Option<String> {
+        let cut = self.wiring.min_cut()?;
+
+        let mut report = format!("Cut {} wires:\n", cut.edges.len());
+        for (a, b) in cut.edges {
+            report += &format!("{}/{}\n", self.wiring.name(a), self.wiring.name(b));
+        }
+
+        Some(report)
     }
 }
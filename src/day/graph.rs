@@ -0,0 +1,171 @@
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// An undirected graph with named vertices and weighted edges.
+///
+/// Adding an edge that already exists adds to its weight.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Graph {
+    names: Vec<String>,
+    indices: HashMap<String, usize>,
+    edges: Vec<HashMap<usize, usize>>,
+}
+
+/// A partition of the vertices in two, the edges between them and their total weight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Cut {
+    pub weight: usize,
+    pub side: Vec<usize>,
+    /// Each crossing edge as `(inside, outside)`, sorted.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl Graph {
+    /// The index of the vertex called `name`, adding it if it is new.
+    pub fn vertex(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.indices.get(name) {
+            return index;
+        }
+
+        self.names.push(name.to_string());
+        self.edges.push(HashMap::new());
+        self.indices.insert(name.to_string(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    pub fn add_edge(&mut self, a: usize, b: usize, weight: usize) {
+        *self.edges[a].entry(b).or_default() += weight;
+        *self.edges[b].entry(a).or_default() += weight;
+    }
+
+    pub fn name(&self, index: usize) -> &str {
+        &self.names[index]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// The edges from `side` to the rest of the graph.
+    fn crossing(&self, side: &[usize]) -> Vec<(usize, usize)> {
+        let inside: HashSet<_> = side.iter().copied().collect();
+        let mut edges: Vec<_> = side
+            .iter()
+            .flat_map(|&a| {
+                self.edges[a]
+                    .keys()
+                    .filter(|b| !inside.contains(b))
+                    .map(move |&b| (a, b))
+            })
+            .collect();
+        edges.sort_unstable();
+
+        edges
+    }
+
+    /// Finds a cut of minimum weight with the Stoer–Wagner algorithm, or `None` if there are
+    /// fewer than two vertices.
+    ///
+    /// Each phase grows a set from one vertex by repeatedly adding the most tightly connected
+    /// remaining vertex. The weight connecting the last vertex added is a minimum cut between it
+    /// and the one added before it, and merging those two vertices leaves the global minimum cut
+    /// either already found or still present in the smaller graph.
+    pub fn min_cut(&self) -> Option<Cut> {
+        let n = self.len();
+        let mut edges = self.edges.clone();
+        let mut members: Vec<Vec<usize>> = (0..n).map(|v| vec![v]).collect();
+        let mut active: Vec<usize> = (0..n).collect();
+        let mut best: Option<Cut> = None;
+
+        while active.len() > 1 {
+            let mut connection = vec![0; n];
+            let mut added = vec![false; n];
+            let mut queue: BinaryHeap<_> = active.iter().map(|&v| (0, v)).collect();
+            let (mut previous, mut last) = (None, None);
+
+            while let Some((weight, v)) = queue.pop() {
+                if added[v] || weight != connection[v] {
+                    continue;
+                }
+                added[v] = true;
+                (previous, last) = (last, Some(v));
+
+                for (&u, &w) in &edges[v] {
+                    if !added[u] {
+                        connection[u] += w;
+                        queue.push((connection[u], u));
+                    }
+                }
+            }
+
+            let (Some(previous), Some(last)) = (previous, last) else {
+                break;
+            };
+
+            if best.as_ref().is_none_or(|b| connection[last] < b.weight) {
+                best = Some(Cut {
+                    weight: connection[last],
+                    side: members[last].clone(),
+                    edges: Vec::new(),
+                });
+            }
+
+            for (u, w) in std::mem::take(&mut edges[last]) {
+                edges[u].remove(&last);
+                if u != previous {
+                    *edges[previous].entry(u).or_default() += w;
+                    *edges[u].entry(previous).or_default() += w;
+                }
+            }
+            let merged = std::mem::take(&mut members[last]);
+            members[previous].extend(merged);
+            active.retain(|&v| v != last);
+        }
+
+        best.map(|cut| Cut {
+            edges: self.crossing(&cut.side),
+            ..cut
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_triangles() {
+        let mut graph = Graph::default();
+        for (a, b) in [
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("x", "y"),
+            ("y", "z"),
+            ("z", "x"),
+        ] {
+            let (a, b) = (graph.vertex(a), graph.vertex(b));
+            graph.add_edge(a, b, 2);
+        }
+        let (c, x) = (graph.vertex("c"), graph.vertex("x"));
+        graph.add_edge(c, x, 1);
+
+        let cut = graph.min_cut().unwrap();
+        let mut side = cut.side.clone();
+        side.sort_unstable();
+
+        assert_eq!(cut.weight, 1);
+        assert!(side == [0, 1, 2] || side == [3, 4, 5]);
+        assert!(cut.edges == [(c, x)] || cut.edges == [(x, c)]);
+    }
+
+    #[test]
+    fn disconnected() {
+        let mut graph = Graph::default();
+        let (a, b) = (graph.vertex("a"), graph.vertex("b"));
+        graph.add_edge(a, b, 1);
+        graph.vertex("c");
+
+        assert_eq!(graph.min_cut().unwrap().weight, 0);
+        assert_eq!(Graph::default().min_cut(), None);
+    }
+}
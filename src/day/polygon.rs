@@ -0,0 +1,56 @@
+/// Twice the signed area of a closed polygon, by the shoelace formula.
+///
+/// Positive for counter-clockwise vertices in a y-up frame, which is clockwise on screen where
+/// y grows downwards. The last vertex connects back to the first.
+pub(crate) fn double_area(vertices: &[(i64, i64)]) -> i64 {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&(x1, y1), &(x2, y2))| x1 * y2 - x2 * y1)
+        .sum()
+}
+
+/// Number of lattice points on the boundary of a closed polygon with lattice vertices.
+pub(crate) fn boundary_points(vertices: &[(i64, i64)]) -> i64 {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&(x1, y1), &(x2, y2))| num::integer::gcd(x2 - x1, y2 - y1))
+        .sum()
+}
+
+/// Number of lattice points strictly inside a closed polygon, by Pick's theorem
+/// `A = I + B / 2 - 1`.
+pub(crate) fn interior_points(vertices: &[(i64, i64)]) -> i64 {
+    (double_area(vertices).abs() - boundary_points(vertices)) / 2 + 1
+}
+
+/// Number of lattice points inside or on a closed polygon, i.e. the number of grid cells covered
+/// when the polygon runs through cell centres.
+pub(crate) fn covered_points(vertices: &[(i64, i64)]) -> i64 {
+    interior_points(vertices) + boundary_points(vertices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn square() {
+        let square = [(0, 0), (2, 0), (2, 2), (0, 2)];
+
+        assert_eq!(double_area(&square), 8);
+        assert_eq!(boundary_points(&square), 8);
+        assert_eq!(interior_points(&square), 1);
+        assert_eq!(covered_points(&square), 9);
+    }
+
+    #[test]
+    fn orientation_and_diagonals() {
+        let triangle = [(0, 0), (0, 4), (4, 0)];
+
+        assert_eq!(double_area(&triangle), -16);
+        assert_eq!(boundary_points(&triangle), 12);
+        assert_eq!(interior_points(&triangle), 3);
+    }
+}
@@ -0,0 +1,165 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+use color_eyre::eyre::{eyre, Report};
+
+/// A grid of single digit costs from 1 to 9, such as the city blocks in Day 17.
+///
+/// Every step costs at least 1, so the Manhattan distance never overestimates the remaining cost
+/// and can be used as the [`shortest_path`] heuristic.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct WeightedGrid {
+    pub stride: usize,
+    pub height: usize,
+    data: Vec<u8>,
+}
+
+impl TryFrom<&str> for WeightedGrid {
+    type Error = Report;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut stride = None;
+        let mut data = Vec::new();
+
+        for line in value.lines().filter(|l| !l.is_empty()) {
+            let row = line
+                .bytes()
+                .map(|b| {
+                    (b'1'..=b'9')
+                        .contains(&b)
+                        .then_some(b - b'0')
+                        .ok_or_else(|| eyre!("Invalid weight: {}", b as char))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if *stride.get_or_insert(row.len()) != row.len() {
+                return Err(eyre!("Ragged grid row: {line}"));
+            }
+            data.extend(row);
+        }
+
+        let stride = stride.unwrap_or(0);
+        let height = data.len().checked_div(stride).unwrap_or(0);
+
+        Ok(Self {
+            stride,
+            height,
+            data,
+        })
+    }
+}
+
+impl WeightedGrid {
+    pub fn get(&self, (x, y): (isize, isize)) -> Option<usize> {
+        if x < 0 || y < 0 || x as usize >= self.stride || y as usize >= self.height {
+            None
+        } else {
+            Some(self.data[y as usize * self.stride + x as usize] as usize)
+        }
+    }
+
+    pub fn bottom_right(&self) -> (isize, isize) {
+        (self.stride as isize - 1, self.height as isize - 1)
+    }
+}
+
+/// Finds the cheapest path from any of `starts` to a state satisfying `is_goal`.
+///
+/// This is Dijkstra's algorithm over arbitrary states, which become A* when `heuristic` returns
+/// a non-zero lower bound on the remaining cost. The heuristic must never overestimate, or the
+/// returned path may not be the cheapest. Returns the total cost and the goal state reached.
+pub(crate) fn shortest_path<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+    mut heuristic: impl FnMut(&S) -> usize,
+) -> Option<(usize, S)>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut states = Vec::new();
+    let mut best = HashMap::new();
+    let mut queue = BinaryHeap::new();
+
+    for start in starts {
+        best.insert(start.clone(), 0);
+        queue.push(Reverse((heuristic(&start), 0, states.len())));
+        states.push(start);
+    }
+
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        let state = states[index].clone();
+        if best.get(&state).is_some_and(|&b| b < cost) {
+            continue;
+        }
+
+        if is_goal(&state) {
+            return Some((cost, state));
+        }
+
+        for (next, step) in neighbours(&state) {
+            let next_cost = cost + step;
+            if best.get(&next).is_some_and(|&b| b <= next_cost) {
+                continue;
+            }
+            best.insert(next.clone(), next_cost);
+
+            queue.push(Reverse((
+                next_cost + heuristic(&next),
+                next_cost,
+                states.len(),
+            )));
+            states.push(next);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_path() {
+        let grid = WeightedGrid::try_from("131\n111\n191\n").unwrap();
+        let goal = grid.bottom_right();
+
+        let neighbours = |&(x, y): &(isize, isize)| {
+            [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+                .into_iter()
+                .filter_map(|p| grid.get(p).map(|w| (p, w)))
+                .collect::<Vec<_>>()
+        };
+        let manhattan = |&(x, y): &(isize, isize)| (goal.0 - x + goal.1 - y) as usize;
+
+        let dijkstra = shortest_path([(0, 0)], neighbours, |&p| p == goal, |_| 0);
+        let a_star = shortest_path([(0, 0)], neighbours, |&p| p == goal, manhattan);
+
+        assert_eq!(dijkstra, Some((4, goal)));
+        assert_eq!(a_star, Some((4, goal)));
+    }
+
+    #[test]
+    fn invalid_weights() {
+        assert!(WeightedGrid::try_from("12\n3x\n").is_err());
+        assert!(WeightedGrid::try_from("12\n30\n").is_err());
+        assert!(WeightedGrid::try_from("12\n3\n").is_err());
+    }
+
+    #[test]
+    fn unreachable() {
+        let path = shortest_path([0u8], |_| Vec::new(), |&s| s == 1, |_| 0);
+        assert_eq!(path, None);
+    }
+
+    #[test]
+    fn start_is_goal() {
+        assert_eq!(
+            shortest_path([0u8], |_| Vec::new(), |&s| s == 0, |_| 0),
+            Some((0, 0))
+        );
+    }
+}
@@ -1,68 +1,286 @@
+use std::time::SystemTime;
+
 use color_eyre::eyre::eyre;
 use color_eyre::Result;
 
+use crate::config::Config;
+use crate::throttle::Throttle;
+
 #[derive(Debug)]
 pub struct Input {
     pub data: String,
 }
 
-fn cache_path(day: u8) -> std::path::PathBuf {
-    format!("input/{day:02}").into()
+fn cache_path(config: &Config, day: u8) -> std::path::PathBuf {
+    config.year_dir("input").join(format!("{day:02}"))
+}
+
+fn checksum_path(config: &Config, day: u8) -> std::path::PathBuf {
+    config.year_dir("input").join(format!("{day:02}.checksum"))
+}
+
+/// 64-bit FNV-1a, enough to notice a cached input being edited by hand.
+pub fn checksum(data: &str) -> u64 {
+    data.bytes().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Days since 1970-01-01 for a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+/// Puzzles unlock at midnight US/Eastern, which in December is always EST (UTC-5).
+pub fn unlock_time(year: u16, day: u8) -> SystemTime {
+    let days = days_from_civil(year as i64, 12, day as i64);
+    let seconds = days * 24 * 60 * 60 + 5 * 60 * 60;
+
+    std::time::UNIX_EPOCH + std::time::Duration::from_secs(seconds as u64)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CacheStatus {
+    Verified,
+    Modified {
+        expected: u64,
+    },
+    /// No checksum was stored, e.g. for files cached before checksums existed.
+    Unverified,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub day: u8,
+    pub size: u64,
+    pub checksum: u64,
+    pub status: CacheStatus,
+}
+
+const SESSION_HINT: &str = "refresh the session token (the `session` cookie from the website) \
+    in the session file or AOC_SESSION";
+
+/// Checks that a response is actual puzzle input rather than an error or login page.
+fn validate(day: u8, status: reqwest::StatusCode, body: &str) -> Result<()> {
+    use reqwest::StatusCode;
+
+    if body.contains("Please log in")
+        || matches!(
+            status,
+            StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        )
+    {
+        return Err(eyre!(
+            "The server rejected the session for day {day} ({status}); {SESSION_HINT}"
+        ));
+    }
+
+    if body.contains("before it unlocks") || status == StatusCode::NOT_FOUND {
+        return Err(eyre!("Day {day} has not unlocked yet ({status})"));
+    }
+
+    if status == StatusCode::INTERNAL_SERVER_ERROR {
+        return Err(eyre!(
+            "Fetching input for day {day} failed with {status}, which usually means a \
+             malformed session token; {SESSION_HINT}"
+        ));
+    }
+
+    if !status.is_success() {
+        return Err(eyre!("Fetching input for day {day} failed with {status}"));
+    }
+
+    if body.trim().is_empty() {
+        return Err(eyre!("The server sent empty input for day {day}"));
+    }
+
+    if body.trim_start().starts_with('<') {
+        return Err(eyre!(
+            "The server sent a web page instead of input for day {day}; {SESSION_HINT}"
+        ));
+    }
+
+    Ok(())
 }
 
 impl Input {
-    pub(crate) fn get(day: u8) -> Result<Self> {
-        let cache = cache_path(day);
+    /// Reads the input for `day` from the `input/<year>/` cache, fetching and caching it on a
+    /// miss.
+    pub fn get(config: &Config, day: u8) -> Result<Self> {
+        let cache = cache_path(config, day);
         if cache.exists() {
-            let data = std::fs::read_to_string(cache)?;
+            let data = std::fs::read_to_string(&cache)?;
+            validate(day, reqwest::StatusCode::OK, &data).map_err(|e| {
+                eyre!(
+                    "{} does not hold puzzle input, delete it to fetch again: {e}",
+                    cache.display()
+                )
+            })?;
+
             Ok(Input { data })
         } else {
-            Ok(Self::get_http(day)?)
+            Ok(Self::get_http(config, day)?)
         }
     }
 
-    fn save(&self, day: u8) -> Result<()> {
-        let path = cache_path(day);
+    /// Reads input from an arbitrary file, or from stdin when `path` is `-`.
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = if path == std::path::Path::new("-") {
+            std::io::read_to_string(std::io::stdin())?
+        } else {
+            std::fs::read_to_string(path)
+                .map_err(|e| eyre!("Could not read {}: {e}", path.display()))?
+        };
+
+        Ok(Input { data })
+    }
+
+    /// Describes the cached input for `day`, if there is one.
+    pub fn cached(config: &Config, day: u8) -> Result<Option<CacheEntry>> {
+        let path = cache_path(config, day);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let data = std::fs::read_to_string(&path)?;
+        let checksum = checksum(&data);
+
+        let stored = checksum_path(config, day);
+        let status = if stored.exists() {
+            let expected = u64::from_str_radix(std::fs::read_to_string(stored)?.trim(), 16)?;
+            if expected == checksum {
+                CacheStatus::Verified
+            } else {
+                CacheStatus::Modified { expected }
+            }
+        } else {
+            CacheStatus::Unverified
+        };
+
+        Ok(Some(CacheEntry {
+            day,
+            size: data.len() as u64,
+            checksum,
+            status,
+        }))
+    }
+
+    /// Removes the cached input for `day`, returning whether there was one.
+    pub fn invalidate(config: &Config, day: u8) -> Result<bool> {
+        let path = cache_path(config, day);
+        let existed = path.exists();
+        if existed {
+            std::fs::remove_file(path)?;
+        }
+
+        let stored = checksum_path(config, day);
+        if stored.exists() {
+            std::fs::remove_file(stored)?;
+        }
+
+        Ok(existed)
+    }
+
+    pub fn is_cached(config: &Config, day: u8) -> bool {
+        cache_path(config, day).exists()
+    }
+
+    /// Fetches the input for `day` unless it is already cached, returning whether it fetched.
+    pub fn prefetch(config: &Config, day: u8) -> Result<bool> {
+        if Self::is_cached(config, day) {
+            Ok(false)
+        } else {
+            Self::get_http(config, day)?;
+            Ok(true)
+        }
+    }
+
+    fn save(&self, config: &Config, day: u8) -> Result<()> {
+        let path = cache_path(config, day);
         let root = path.parent().unwrap();
         if !root.exists() {
             std::fs::create_dir_all(root)?;
         }
         std::fs::write(&path, &self.data)?;
+        std::fs::write(
+            checksum_path(config, day),
+            format!("{:016x}\n", checksum(&self.data)),
+        )?;
 
         Ok(())
     }
 
-    fn get_http(day: u8) -> Result<Input> {
-        let session = std::fs::read_to_string("session_token")?;
-        let session = session.trim();
+    fn get_http(config: &Config, day: u8) -> Result<Input> {
+        let client = crate::client::Client::new(config)?;
 
-        let throttle_path = std::path::Path::new("throttle");
+        let path = format!("day/{day}/input");
+        let endpoint = format!("{}/{path}", config.year);
+        let mut throttle = Throttle::load(config)?;
+        throttle.check(&endpoint, SystemTime::now())?;
 
-        if let Ok(metadata) = throttle_path.metadata() {
-            if let Ok(time_since_last) = metadata.modified()?.elapsed() {
-                if time_since_last < std::time::Duration::from_secs(15 * 60) {
-                    return Err(eyre!("Throttled"));
-                }
-            }
-        }
+        let response = client.get(&path)?;
+        let status = response.status();
+        let data = response
+            .text()
+            .map_err(color_eyre::Report::from)
+            .and_then(|data| validate(day, status, &data).map(|()| data));
+        throttle.record(&endpoint, data.is_ok(), SystemTime::now());
+        throttle.save()?;
 
-        std::fs::File::create(throttle_path)?;
+        let input = Input { data: data? };
+        input.save(config, day)?;
 
-        let client = reqwest::blocking::ClientBuilder::new()
-            .user_agent("personal tool via Jacques jqschutte@gmail.com")
-            .build()?;
+        Ok(input)
+    }
+}
 
-        let url = format!("https://adventofcode.com/2023/day/{day}/input");
+#[cfg(test)]
+mod tests {
+    use reqwest::StatusCode;
 
-        let response = client
-            .get(url)
-            .header("cookie", format!("session={session}"))
-            .send()?;
+    use super::*;
 
-        let data = response.text()?;
-        let input = Input { data };
-        input.save(day)?;
+    #[test]
+    fn unlock() {
+        let since_epoch = |t: SystemTime| t.duration_since(std::time::UNIX_EPOCH).unwrap();
 
-        Ok(input)
+        assert_eq!(since_epoch(unlock_time(2023, 1)).as_secs(), 1701406800);
+        assert_eq!(since_epoch(unlock_time(2023, 25)).as_secs(), 1703480400);
+        assert_eq!(since_epoch(unlock_time(2016, 1)).as_secs(), 1480568400);
+    }
+
+    #[test]
+    fn checksum_notices_newlines() {
+        assert_eq!(checksum(""), 0xcbf29ce484222325);
+        assert_ne!(checksum("1abc2\n"), checksum("1abc2\n\n"));
+    }
+
+    #[test]
+    fn accepts_puzzle_input() {
+        assert!(validate(1, StatusCode::OK, "1abc2\npqr3stu8vwx\n").is_ok());
+    }
+
+    #[test]
+    fn rejects_error_pages() {
+        let login = "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n";
+        let error = validate(1, StatusCode::BAD_REQUEST, login).unwrap_err();
+        assert!(error.to_string().contains("session token"));
+
+        let early = "Please don't repeatedly request this endpoint before it unlocks! \
+            The calendar countdown is synchronized with the server time; the link will be \
+            enabled on the calendar the instant this puzzle becomes available.\n";
+        let error = validate(25, StatusCode::NOT_FOUND, early).unwrap_err();
+        assert!(error.to_string().contains("not unlocked"));
+
+        assert!(validate(1, StatusCode::INTERNAL_SERVER_ERROR, "").is_err());
+        assert!(validate(1, StatusCode::OK, "").is_err());
+        assert!(validate(1, StatusCode::OK, "<!DOCTYPE html>\n<html>").is_err());
     }
 }
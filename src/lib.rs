@@ -1,2 +1,7 @@
+pub mod answers;
+pub mod client;
+pub mod config;
 pub mod day;
-mod input;
+pub mod input;
+pub mod submit;
+pub mod throttle;
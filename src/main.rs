@@ -1,14 +1,52 @@
-use advent2023::day::get;
-use color_eyre::Result;
+use std::io::Write;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::{Duration, Instant};
 
-fn print_section(label: &str, output: Option<&str>, time: &mut std::time::Instant) {
+use advent2023::answers::{Answers, Hint, Verdict};
+use advent2023::client::Client;
+use advent2023::config::Config;
+use advent2023::day::{self, Info};
+use advent2023::input::{CacheEntry, CacheStatus, Input};
+use advent2023::submit::{self, Cooldown, Response};
+use advent2023::throttle::format_duration;
+use color_eyre::eyre::{eyre, Result};
+
+/// Minimum time between two input downloads when fetching several days.
+const FETCH_INTERVAL: Duration = Duration::from_secs(5);
+
+fn verdict_note(verdict: Option<&Verdict>) -> String {
+    match verdict {
+        None => String::new(),
+        Some(Verdict::Match) => " (correct)".to_string(),
+        Some(Verdict::Mismatch(expected)) => format!(" (expected {expected})"),
+        Some(Verdict::Unknown) => " (unknown)".to_string(),
+    }
+}
+
+fn colour_answer(answer: &str, verdict: Option<&Verdict>) -> String {
+    use color_eyre::owo_colors::OwoColorize;
+
+    let note = verdict_note(verdict);
+    match verdict {
+        None | Some(Verdict::Match) => format!("{}{}", answer.bright_green(), note.green()),
+        Some(Verdict::Mismatch(_)) => format!("{}{}", answer.bright_red(), note.red()),
+        Some(Verdict::Unknown) => format!("{}{}", answer.bright_yellow(), note.yellow()),
+    }
+}
+
+fn print_section(
+    label: &str,
+    output: Option<&str>,
+    verdict: Option<&Verdict>,
+    time: &mut std::time::Instant,
+) {
     use color_eyre::owo_colors::OwoColorize;
 
     match output {
         Some(output) => println!(
             "{}: {}\nTook {}{}\n",
             label.bright_yellow(),
-            output.bright_green(),
+            colour_answer(output, verdict),
             time.elapsed().as_millis().bright_blue(),
             "ms".bright_blue(),
         ),
@@ -23,35 +61,664 @@ fn print_section(label: &str, output: Option<&str>, time: &mut std::time::Instan
     *time = std::time::Instant::now();
 }
 
-fn print_day(day: u8) {
+fn print_failure(label: &str, error: &str, time: &mut std::time::Instant) {
     use color_eyre::owo_colors::OwoColorize;
 
-    println!("Day {}", day.bright_purple());
+    println!(
+        "{} failed: {}\nTook {}{}\n",
+        label.bright_yellow(),
+        error.bright_red(),
+        time.elapsed().as_millis().bright_blue(),
+        "ms".bright_blue(),
+    );
+
+    *time = std::time::Instant::now();
 }
 
-fn main() -> Result<()> {
-    color_eyre::install()?;
+fn print_skipped(label: &str) {
+    use color_eyre::owo_colors::OwoColorize;
+
+    println!("{} not implemented\n", label.bright_yellow());
+}
+
+fn print_day(info: &Info) {
+    use color_eyre::owo_colors::OwoColorize;
+
+    println!("Day {}: {}", info.number.bright_purple(), info.title);
+}
+
+fn print_list() {
+    use color_eyre::owo_colors::OwoColorize;
+
+    let mark = |implemented: bool| {
+        if implemented {
+            format!("{}", "yes".bright_green())
+        } else {
+            format!("{}", "no".bright_red())
+        }
+    };
+
+    for info in day::registry() {
+        println!(
+            "{:>3}  part 1: {:<3}  part 2: {:<3}  {}",
+            info.number.bright_purple(),
+            mark(info.part1),
+            mark(info.part2),
+            info.title,
+        );
+    }
+}
+
+/// Parses a day selection such as `7`, `all`, `1..=16`, `1..5` or `3,7,12`.
+/// Ranges and single days can be mixed in a comma separated list.
+fn parse_selection(selection: &str) -> Result<Vec<u8>> {
+    if selection == "all" {
+        return Ok(day::registry().map(|i| i.number).collect());
+    }
+
+    let parse_day = |s: &str| -> Result<u8> {
+        let day = s.trim().parse().map_err(|_| eyre!("Invalid day: {s:?}"))?;
+        Ok(day::info(day)?.number)
+    };
+
+    let mut days = Vec::new();
+    for part in selection.split(',') {
+        if let Some((start, end)) = part.split_once("..=") {
+            days.extend(parse_day(start)?..=parse_day(end)?);
+        } else if let Some((start, end)) = part.split_once("..") {
+            days.extend(parse_day(start)?..parse_day(end)?);
+        } else {
+            days.push(parse_day(part)?);
+        }
+    }
+
+    days.sort_unstable();
+    days.dedup();
+
+    if days.is_empty() {
+        Err(eyre!("Empty day selection: {selection:?}"))
+    } else {
+        Ok(days)
+    }
+}
+
+#[derive(Debug)]
+enum Outcome {
+    Answer(String, Option<Verdict>),
+    Failed(String),
+    Unimplemented,
+    Skipped,
+}
+
+#[derive(Debug)]
+struct Report {
+    day: u8,
+    part1: Outcome,
+    part2: Outcome,
+    elapsed: Duration,
+}
+
+impl Report {
+    fn has_failures(&self) -> bool {
+        [&self.part1, &self.part2].into_iter().any(|o| {
+            matches!(
+                o,
+                Outcome::Failed(_) | Outcome::Answer(_, Some(Verdict::Mismatch(_)))
+            )
+        })
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        format!("panicked: {s}")
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        format!("panicked: {s}")
+    } else {
+        "panicked".to_string()
+    }
+}
+
+/// Runs `f`, turning both errors and panics (such as `todo!()`) into a message.
+fn guard<T>(f: impl FnOnce() -> Result<T>) -> std::result::Result<T, String> {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(v)) => Ok(v),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(payload) => Err(panic_message(payload)),
+    }
+}
+
+fn run_part(
+    label: &str,
+    f: impl FnOnce() -> Result<String>,
+    check: impl FnOnce(&str) -> Option<Verdict>,
+    time: &mut Instant,
+) -> Outcome {
+    match guard(f) {
+        Ok(answer) => {
+            let verdict = check(&answer);
+            print_section(label, Some(&answer), verdict.as_ref(), time);
+            Outcome::Answer(answer, verdict)
+        }
+        Err(e) => {
+            print_failure(label, &e, time);
+            Outcome::Failed(e)
+        }
+    }
+}
+
+fn run_guarded(config: &Config, info: &Info, verify: bool) -> Report {
+    print_day(info);
+
+    if info.is_stub() {
+        print_skipped("Day");
+
+        return Report {
+            day: info.number,
+            part1: Outcome::Unimplemented,
+            part2: Outcome::Unimplemented,
+            elapsed: Duration::ZERO,
+        };
+    }
+
+    let start = Instant::now();
+    let mut now = Instant::now();
+
+    let mut solver = info.create();
+    let answers = verify.then(|| Answers::load(config, info.number));
+
+    let (part1, part2) = match guard(|| solver.setup(&Input::get(config, info.number)?.data)) {
+        Ok(()) => {
+            print_section("Setup", None, None, &mut now);
+
+            let mut part = |number, label, implemented, f: &dyn Fn() -> Result<String>| {
+                if implemented {
+                    let check = |answer: &str| match &answers {
+                        Some(Ok(answers)) => Some(answers.check(number, answer)),
+                        Some(Err(_)) => Some(Verdict::Unknown),
+                        None => None,
+                    };
+                    run_part(label, f, check, &mut now)
+                } else {
+                    print_skipped(label);
+                    Outcome::Unimplemented
+                }
+            };
+
+            let part1 = part(1, "Part 1", info.part1, &|| solver.part1());
+            let part2 = part(2, "Part 2", info.part2, &|| solver.part2());
+
+            (part1, part2)
+        }
+        Err(e) => {
+            print_failure("Setup", &e, &mut now);
+
+            (Outcome::Failed(format!("setup: {e}")), Outcome::Skipped)
+        }
+    };
+
+    Report {
+        day: info.number,
+        part1,
+        part2,
+        elapsed: start.elapsed(),
+    }
+}
+
+fn print_summary(reports: &[Report]) {
+    use color_eyre::owo_colors::OwoColorize;
+
+    let text = |o: &Outcome| match o {
+        Outcome::Answer(a, v) => format!("{a}{}", verdict_note(v.as_ref())),
+        Outcome::Failed(e) => e.clone(),
+        Outcome::Unimplemented => "not implemented".to_string(),
+        Outcome::Skipped => "-".to_string(),
+    };
+
+    let width = |f: fn(&Report) -> &Outcome, label: &str| {
+        reports
+            .iter()
+            .map(|r| text(f(r)).len())
+            .max()
+            .unwrap_or(0)
+            .max(label.len())
+    };
+    let width1 = width(|r| &r.part1, "Part 1");
+    let width2 = width(|r| &r.part2, "Part 2");
+
+    let cell = |o: &Outcome, width: usize| match o {
+        Outcome::Answer(a, v) => format!(
+            "{}{}",
+            colour_answer(a, v.as_ref()),
+            " ".repeat(width - text(o).len())
+        ),
+        Outcome::Failed(e) => format!("{:<width$}", e.bright_red()),
+        Outcome::Unimplemented => format!("{:<width$}", "not implemented".bright_black()),
+        Outcome::Skipped => format!("{:<width$}", "-"),
+    };
+
+    println!(
+        "{:>3}  {:<width1$}  {:<width2$}  {}",
+        "Day".bright_yellow(),
+        "Part 1".bright_yellow(),
+        "Part 2".bright_yellow(),
+        "Time".bright_yellow(),
+    );
+
+    for r in reports {
+        println!(
+            "{:>3}  {}  {}  {}{}",
+            r.day.bright_purple(),
+            cell(&r.part1, width1),
+            cell(&r.part2, width2),
+            r.elapsed.as_millis().bright_blue(),
+            "ms".bright_blue(),
+        );
+    }
+
+    let total: Duration = reports.iter().map(|r| r.elapsed).sum();
+    let failed = reports.iter().filter(|r| r.has_failures()).count();
+
+    println!(
+        "\n{} days, {} with failures, total {}{}",
+        reports.len(),
+        failed.bright_red(),
+        total.as_millis().bright_blue(),
+        "ms".bright_blue(),
+    );
+}
+
+fn run_all(days: &[u8], verify: bool) -> Result<()> {
+    let config = Config::load()?;
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+
+    let reports: Vec<_> = days
+        .iter()
+        .filter_map(|&day| day::info(day).ok())
+        .map(|info| run_guarded(&config, info, verify))
+        .collect();
 
-    let day: u8 = std::env::args()
-        .nth(1)
-        .expect("Need a day input")
-        .parse()
-        .expect("Invalid input");
+    std::panic::set_hook(hook);
 
-    print_day(day);
+    print_summary(&reports);
+
+    let failed = reports.iter().filter(|r| r.has_failures()).count();
+    if failed > 0 {
+        Err(eyre!("{failed} of {} days had failures", reports.len()))
+    } else {
+        Ok(())
+    }
+}
+
+fn run(day: u8, input: Option<&str>, verify: bool, report: bool, visualize: bool) -> Result<()> {
+    let info = day::info(day)?;
+    print_day(info);
+
+    if info.is_stub() {
+        return Err(eyre!("Day {day} is not implemented yet"));
+    }
 
     let mut now = std::time::Instant::now();
 
-    let mut day = get(day);
+    let mut day = info.create();
+
+    let config = Config::load()?;
+    let input = match input {
+        Some(path) => Input::from_path(path)?,
+        None => Input::get(&config, info.number)?,
+    };
+
+    let answers = verify
+        .then(|| Answers::load(&config, info.number))
+        .transpose()?;
+
+    day.setup(&input.data)?;
+    print_section("Setup", None, None, &mut now);
+
+    if report {
+        match day.report() {
+            Some(report) => println!("{report}"),
+            None => println!("Day {} has no report\n", info.number),
+        }
+    }
+
+    let mut mismatch = false;
+
+    if info.part1 {
+        let part1 = day.part1()?;
+        let verdict = answers.as_ref().map(|a| a.check(1, &part1));
+        mismatch |= matches!(verdict, Some(Verdict::Mismatch(_)));
+        print_section("Part 1", Some(&part1), verdict.as_ref(), &mut now);
+    } else {
+        print_skipped("Part 1");
+    }
+
+    if info.part2 {
+        let part2 = day.part2()?;
+        let verdict = answers.as_ref().map(|a| a.check(2, &part2));
+        mismatch |= matches!(verdict, Some(Verdict::Mismatch(_)));
+        print_section("Part 2", Some(&part2), verdict.as_ref(), &mut now);
+    } else {
+        print_skipped("Part 2");
+    }
+
+    if visualize {
+        match day.visualize() {
+            Some(picture) => print!("{picture}"),
+            None => println!("Day {} has no visualisation", info.number),
+        }
+    }
+
+    if mismatch {
+        Err(eyre!("Day {} did not match the answer store", info.number))
+    } else {
+        Ok(())
+    }
+}
+
+/// Records `answer` as the confirmed answer for `part` of `day`.
+fn confirm(day: u8, part: u8, answer: &str) -> Result<()> {
+    use color_eyre::owo_colors::OwoColorize;
+
+    let info = day::info(day)?;
+    let config = Config::load()?;
+    let mut answers = Answers::load(&config, info.number)?;
+    answers.set(part, answer)?;
+    answers.save(&config, info.number)?;
+
+    println!(
+        "Day {} part {}: confirmed {}",
+        day.bright_purple(),
+        part,
+        answer.bright_green()
+    );
+
+    Ok(())
+}
+
+/// Submits `answer` for `part` of `day`, solving the day first when no answer is given.
+fn submit_answer(day: u8, part: u8, answer: Option<String>) -> Result<()> {
+    use color_eyre::owo_colors::OwoColorize;
+
+    let info = day::info(day)?;
+    let config = Config::load()?;
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let implemented = match part {
+                1 => info.part1,
+                2 => info.part2,
+                _ => return Err(eyre!("Invalid part: {part}")),
+            };
+            if !implemented {
+                return Err(eyre!("Day {day} part {part} is not implemented yet"));
+            }
+
+            let mut solver = info.create();
+            solver.setup(&Input::get(&config, day)?.data)?;
+            if part == 1 {
+                solver.part1()?
+            } else {
+                solver.part2()?
+            }
+        }
+    };
 
-    day.setup()?;
-    print_section("Setup", None, &mut now);
+    println!(
+        "Submitting {} for day {} part {}",
+        answer.bright_green(),
+        day.bright_purple(),
+        part
+    );
 
-    let part1 = day.part1()?;
-    print_section("Part 1", Some(&part1), &mut now);
+    let client = Client::new(&config)?;
+    let mut answers = Answers::load(&config, day)?;
+    let mut cooldown = Cooldown::load(&config)?;
+    let response = submit::submit(
+        &client,
+        day,
+        part,
+        &answer,
+        &mut answers,
+        &mut cooldown,
+        std::time::SystemTime::now(),
+    );
+    answers.save(&config, day)?;
+    cooldown.save()?;
 
-    let part2 = day.part2()?;
-    print_section("Part 2", Some(&part2), &mut now);
+    match response? {
+        Response::Correct => println!("{}", "That's the right answer!".bright_green()),
+        Response::Wrong { hint, wait } => {
+            let hint = match hint {
+                Some(Hint::TooHigh) => " (too high)",
+                Some(Hint::TooLow) => " (too low)",
+                None => "",
+            };
+            println!(
+                "{}{}",
+                "That's not the right answer".bright_red(),
+                hint.red()
+            );
+            println!("Wait {}s before trying again", wait.as_secs().bright_blue());
+        }
+        Response::TooSoon { wait } => println!(
+            "{} {}s left to wait",
+            "Answered too recently.".bright_yellow(),
+            wait.as_secs().bright_blue()
+        ),
+        Response::WrongLevel => println!(
+            "{}",
+            "Not the current level; is this part already solved?".bright_yellow()
+        ),
+        Response::Unknown(text) => println!("{} {text}", "Unrecognised response:".bright_red()),
+    }
 
     Ok(())
 }
+
+/// Fetches the input for every unlocked day in `days` that is not cached yet.
+fn fetch(days: &[u8]) -> Result<()> {
+    use color_eyre::owo_colors::OwoColorize;
+
+    let config = Config::load()?;
+    let mut failures = 0;
+    let mut last_request: Option<Instant> = None;
+
+    for &day in days {
+        print!("Day {:>2}: ", day.bright_purple());
+        std::io::stdout().flush()?;
+
+        let now = std::time::SystemTime::now();
+        if let Ok(wait) = advent2023::input::unlock_time(config.year, day).duration_since(now) {
+            println!("unlocks in {}", format_duration(wait).bright_blue());
+            continue;
+        }
+
+        if Input::is_cached(&config, day) {
+            println!("already cached");
+            continue;
+        }
+
+        if let Some(last) = last_request {
+            std::thread::sleep(FETCH_INTERVAL.saturating_sub(last.elapsed()));
+        }
+        last_request = Some(Instant::now());
+
+        match Input::prefetch(&config, day) {
+            Ok(true) => println!("{}", "fetched".bright_green()),
+            Ok(false) => println!("already cached"),
+            Err(e) => {
+                failures += 1;
+                println!("{}", e.bright_red());
+            }
+        }
+    }
+
+    if failures > 0 {
+        Err(eyre!("{failures} days could not be fetched"))
+    } else {
+        Ok(())
+    }
+}
+
+fn print_cache_entry(entry: &CacheEntry) {
+    use color_eyre::owo_colors::OwoColorize;
+
+    let status = match entry.status {
+        CacheStatus::Verified => format!("{}", "verified".bright_green()),
+        CacheStatus::Modified { expected } => {
+            format!("{} (fetched as {expected:016x})", "modified".bright_red())
+        }
+        CacheStatus::Unverified => format!("{}", "no checksum".bright_yellow()),
+    };
+
+    println!(
+        "{:>3}  {:>6} bytes  {:016x}  {status}",
+        entry.day.bright_purple(),
+        entry.size,
+        entry.checksum,
+    );
+}
+
+/// Lists, verifies or invalidates the input cache for the configured year.
+fn cache(command: &str, args: &mut impl Iterator<Item = String>) -> Result<()> {
+    use color_eyre::owo_colors::OwoColorize;
+
+    let config = Config::load()?;
+
+    match command {
+        "list" => {
+            for info in day::registry() {
+                match Input::cached(&config, info.number)? {
+                    Some(entry) => print_cache_entry(&entry),
+                    None => println!("{:>3}  not cached", info.number.bright_purple()),
+                }
+            }
+
+            Ok(())
+        }
+        "verify" => {
+            let mut modified = 0;
+            for info in day::registry() {
+                if let Some(entry) = Input::cached(&config, info.number)? {
+                    if entry.status != CacheStatus::Verified {
+                        print_cache_entry(&entry);
+                    }
+                    if matches!(entry.status, CacheStatus::Modified { .. }) {
+                        modified += 1;
+                    }
+                }
+            }
+
+            if modified > 0 {
+                Err(eyre!(
+                    "{modified} cached inputs were modified since they were fetched"
+                ))
+            } else {
+                Ok(())
+            }
+        }
+        "invalidate" => {
+            let usage = || eyre!("Usage: cache invalidate <day>");
+            let day = day::info(args.next().ok_or_else(usage)?.parse()?)?.number;
+            if Input::invalidate(&config, day)? {
+                println!("Day {}: removed cached input", day.bright_purple());
+            } else {
+                println!("Day {}: nothing cached", day.bright_purple());
+            }
+
+            Ok(())
+        }
+        _ => Err(eyre!("Usage: cache <list|verify|invalidate <day>>")),
+    }
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let mut args = std::env::args().skip(1);
+    let selection = args
+        .next()
+        .ok_or_else(|| eyre!("Need a day input, e.g. `7`, `all`, `1..=16` or `3,7,12`"))?;
+
+    match selection.as_str() {
+        "list" => {
+            print_list();
+            return Ok(());
+        }
+        "confirm" => {
+            let usage = || eyre!("Usage: confirm <day> <part> <answer>");
+            let day = args.next().ok_or_else(usage)?.parse()?;
+            let part = args.next().ok_or_else(usage)?.parse()?;
+            let answer = args.next().ok_or_else(usage)?;
+            return confirm(day, part, &answer);
+        }
+        "fetch" => {
+            let days = parse_selection(args.next().as_deref().unwrap_or("all"))?;
+            return fetch(&days);
+        }
+        "cache" => {
+            let command = args.next().unwrap_or_default();
+            return cache(&command, &mut args);
+        }
+        "submit" => {
+            let usage = || eyre!("Usage: submit <day> <part> [answer]");
+            let day = args.next().ok_or_else(usage)?.parse()?;
+            let part = args.next().ok_or_else(usage)?.parse()?;
+            return submit_answer(day, part, args.next());
+        }
+        _ => {}
+    }
+
+    let mut input = None;
+    let mut verify = false;
+    let mut report = false;
+    let mut visualize = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input = Some(args.next().ok_or_else(|| eyre!("--input needs a path"))?),
+            "--verify" => verify = true,
+            "--report" => report = true,
+            "--visualize" => visualize = true,
+            _ => return Err(eyre!("Unknown argument: {arg}")),
+        }
+    }
+
+    if verify && input.is_some() {
+        return Err(eyre!(
+            "--verify checks the cached puzzle input, not --input"
+        ));
+    }
+
+    match parse_selection(&selection)?[..] {
+        [day] => run(day, input.as_deref(), verify, report, visualize),
+        _ if input.is_some() => Err(eyre!("--input can only be used with a single day")),
+        _ if report => Err(eyre!("--report can only be used with a single day")),
+        _ if visualize => Err(eyre!("--visualize can only be used with a single day")),
+        ref days => run_all(days, verify),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection() {
+        assert_eq!(parse_selection("7").unwrap(), [7]);
+        assert_eq!(parse_selection("1..=3").unwrap(), [1, 2, 3]);
+        assert_eq!(parse_selection("1..3").unwrap(), [1, 2]);
+        assert_eq!(parse_selection("12,3,7,3").unwrap(), [3, 7, 12]);
+        assert_eq!(parse_selection("1..=2,5").unwrap(), [1, 2, 5]);
+        assert_eq!(parse_selection("all").unwrap().len(), 25);
+    }
+
+    #[test]
+    fn invalid_selection() {
+        assert!(parse_selection("0").is_err());
+        assert!(parse_selection("26").is_err());
+        assert!(parse_selection("3..3").is_err());
+        assert!(parse_selection("x").is_err());
+    }
+}
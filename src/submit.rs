@@ -0,0 +1,372 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+
+use crate::answers::{Answers, Hint};
+use crate::client::Client;
+use crate::config::Config;
+
+/// What the site said about a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    Correct,
+    Wrong {
+        hint: Option<Hint>,
+        wait: Duration,
+    },
+    TooSoon {
+        wait: Duration,
+    },
+    /// The site thinks this part is not the current one, usually because it is already solved.
+    WrongLevel,
+    Unknown(String),
+}
+
+/// How long to wait when the site does not say.
+const DEFAULT_WAIT: Duration = Duration::from_secs(60);
+
+/// The wait before the next submission, persisted in the `cooldown` file under the data directory
+/// as a single unix timestamp. The site enforces it across the whole account, not per day.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Cooldown {
+    path: PathBuf,
+    until: Option<u64>,
+}
+
+impl Cooldown {
+    pub fn load(config: &Config) -> Result<Self> {
+        Self::load_from(config.data_dir.join("cooldown"))
+    }
+
+    pub fn load_from(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let until = if path.exists() {
+            let contents = std::fs::read_to_string(&path)?;
+            let contents = contents.trim();
+            (!contents.is_empty())
+                .then(|| contents.parse())
+                .transpose()?
+        } else {
+            None
+        };
+
+        Ok(Self { path, until })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(root) = self.path.parent().filter(|r| !r.as_os_str().is_empty()) {
+            std::fs::create_dir_all(root)?;
+        }
+        let contents = self.until.map(|until| format!("{until}\n"));
+        std::fs::write(&self.path, contents.unwrap_or_default())?;
+
+        Ok(())
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Extracts the text of the `<article>` holding the verdict, without tags or repeated spaces.
+fn article_text(page: &str) -> String {
+    let article = page
+        .split_once("<article>")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(page, |(article, _)| article);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parses durations like "one minute", "5 minutes" or "You have 1m 2s left to wait".
+fn parse_wait(text: &str) -> Option<Duration> {
+    if let Some((before, _)) = text.split_once(" left to wait") {
+        let amount = before.rsplit("You have ").next()?;
+        let mut seconds = 0;
+        for part in amount.split_whitespace() {
+            let (value, unit) = part.split_at(part.len() - 1);
+            let value: u64 = value.parse().ok()?;
+            seconds += match unit {
+                "h" => value * 3600,
+                "m" => value * 60,
+                "s" => value,
+                _ => return None,
+            };
+        }
+
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let (_, after) = text.split_once("wait ")?;
+    let mut words = after.split_whitespace();
+    let value = match words.next()? {
+        "one" => 1,
+        "a" => 1,
+        v => v.parse().ok()?,
+    };
+
+    match words.next()? {
+        "minute" | "minutes" => Some(Duration::from_secs(value * 60)),
+        "second" | "seconds" => Some(Duration::from_secs(value)),
+        _ => None,
+    }
+}
+
+pub fn parse_response(page: &str) -> Response {
+    let text = article_text(page);
+
+    if text.contains("That's the right answer") {
+        Response::Correct
+    } else if text.contains("That's not the right answer") {
+        let hint = if text.contains("too high") {
+            Some(Hint::TooHigh)
+        } else if text.contains("too low") {
+            Some(Hint::TooLow)
+        } else {
+            None
+        };
+
+        Response::Wrong {
+            hint,
+            wait: parse_wait(&text).unwrap_or(DEFAULT_WAIT),
+        }
+    } else if text.contains("You gave an answer too recently") {
+        Response::TooSoon {
+            wait: parse_wait(&text).unwrap_or(DEFAULT_WAIT),
+        }
+    } else if text.contains("You don't seem to be solving the right level") {
+        Response::WrongLevel
+    } else {
+        Response::Unknown(text)
+    }
+}
+
+/// Submits `answer` for `part` of `day`, refusing locally when `answers` already rules it out or
+/// the previous submission's cooldown has not passed, and recording the outcome in `answers` and
+/// `cooldown`.
+pub fn submit(
+    client: &Client,
+    day: u8,
+    part: u8,
+    answer: &str,
+    answers: &mut Answers,
+    cooldown: &mut Cooldown,
+    now: SystemTime,
+) -> Result<Response> {
+    if !(1..=2).contains(&part) {
+        return Err(eyre!("Invalid part: {part}"));
+    }
+
+    if let Some(reason) = answers.refusal(part, answer) {
+        return Err(eyre!("Refusing to submit: {reason}"));
+    }
+
+    let now = unix_seconds(now);
+    if let Some(until) = cooldown.until.filter(|&until| until > now) {
+        return Err(eyre!("Still cooling down, {}s left to wait", until - now));
+    }
+
+    let level = part.to_string();
+    let response = client.post_form(
+        &format!("day/{day}/answer"),
+        &[("level", &level), ("answer", answer)],
+    )?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(eyre!("Submission failed with {status}"));
+    }
+
+    let response = parse_response(&response.text()?);
+    match &response {
+        Response::Correct => {
+            answers.set(part, answer)?;
+            cooldown.until = None;
+        }
+        Response::Wrong { hint, wait } => {
+            answers.reject(part, answer, *hint);
+            cooldown.until = Some(now + wait.as_secs());
+        }
+        Response::TooSoon { wait } => {
+            cooldown.until = Some(now + wait.as_secs());
+        }
+        Response::WrongLevel | Response::Unknown(_) => {}
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    use super::*;
+
+    const CORRECT: &str = "<main>\n<article><p>That's the right answer!  You are <span \
+        class=\"day-success\">one gold star</span> closer to restoring snow operations. \
+        <a href=\"/2023/day/1#part2\">[Continue to Part Two]</a></p></article>\n</main>";
+
+    const TOO_HIGH: &str = "<main>\n<article><p>That's not the right answer; your answer is \
+        too high.  If you're stuck, make sure you're using the full input data.  Please wait \
+        one minute before trying again. <a href=\"/2023/day/1\">[Return to Day 1]</a></p>\
+        </article>\n</main>";
+
+    const TOO_SOON: &str = "<main>\n<article><p>You gave an answer too recently; you have to \
+        wait after submitting an answer before trying again.  You have 1m 32s left to wait. \
+        <a href=\"/2023/day/1\">[Return to Day 1]</a></p></article>\n</main>";
+
+    /// Serves `body` to a single request and hands back the request line and form body.
+    fn serve(body: &'static str) -> (Client, JoinHandle<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = Config {
+            base_url: format!("http://{}", listener.local_addr().unwrap()),
+            session: Some("token".to_string()),
+            ..Config::default()
+        };
+
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+
+            let mut length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+
+            let mut form = vec![0; length];
+            reader.read_exact(&mut form).unwrap();
+
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+
+            (
+                request_line.trim().to_string(),
+                String::from_utf8(form).unwrap(),
+            )
+        });
+
+        (Client::new(&config).unwrap(), handle)
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_response(CORRECT), Response::Correct);
+        assert_eq!(
+            parse_response(TOO_HIGH),
+            Response::Wrong {
+                hint: Some(Hint::TooHigh),
+                wait: Duration::from_secs(60),
+            }
+        );
+        assert_eq!(
+            parse_response(TOO_SOON),
+            Response::TooSoon {
+                wait: Duration::from_secs(92)
+            }
+        );
+        assert_eq!(
+            parse_wait("Please wait 5 minutes before trying again."),
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(
+            parse_response("<article><p>That's not the right answer.</p></article>"),
+            Response::Wrong {
+                hint: None,
+                wait: DEFAULT_WAIT,
+            }
+        );
+    }
+
+    #[test]
+    fn submit_correct() {
+        let (client, server) = serve(CORRECT);
+        let mut answers = Answers::default();
+        let mut cooldown = Cooldown::default();
+
+        let now = SystemTime::now();
+        let response = submit(&client, 1, 2, "281", &mut answers, &mut cooldown, now).unwrap();
+        let (request, form) = server.join().unwrap();
+
+        assert_eq!(response, Response::Correct);
+        assert_eq!(request, "POST /2023/day/1/answer HTTP/1.1");
+        assert_eq!(form, "level=2&answer=281");
+        assert_eq!(answers.part2.as_deref(), Some("281"));
+        assert_eq!(cooldown.until, None);
+    }
+
+    #[test]
+    fn submit_wrong_is_remembered() {
+        let (client, server) = serve(TOO_HIGH);
+        let mut answers = Answers::default();
+        let mut cooldown = Cooldown::default();
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+
+        submit(&client, 1, 1, "500", &mut answers, &mut cooldown, now).unwrap();
+        server.join().unwrap();
+
+        assert_eq!(cooldown.until, Some(1_060));
+        assert!(answers.refusal(1, "600").is_some());
+
+        let later = now + Duration::from_secs(120);
+        let error = submit(&client, 1, 1, "501", &mut answers, &mut cooldown, later).unwrap_err();
+        assert!(error.to_string().contains("500 was already too high"));
+
+        // The cooldown applies to every day, not just the one answered.
+        let mut other = Answers::default();
+        let error = submit(&client, 2, 1, "400", &mut other, &mut cooldown, now).unwrap_err();
+        assert!(error.to_string().contains("cooling down"));
+    }
+
+    #[test]
+    fn cooldown_round_trip() {
+        let path = std::env::temp_dir().join(format!("cooldown-test-{}", std::process::id()));
+
+        let mut cooldown = Cooldown::load_from(&path).unwrap();
+        assert_eq!(cooldown.until, None);
+
+        cooldown.until = Some(1_060);
+        cooldown.save().unwrap();
+        assert_eq!(Cooldown::load_from(&path).unwrap(), cooldown);
+
+        cooldown.until = None;
+        cooldown.save().unwrap();
+        let loaded = Cooldown::load_from(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, cooldown);
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+
+use crate::config::Config;
+
+/// Minimum time between two requests to the same endpoint.
+const INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Longest wait imposed after repeated failures.
+const MAX_BACKOFF: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Entry {
+    last: u64,
+    failures: u32,
+}
+
+/// Per-endpoint request history, persisted in the `throttle` file under the data directory as
+/// `<endpoint> <unix seconds> <consecutive failures>` lines. Endpoints are keyed by year, e.g.
+/// `2023/day/1/input`.
+///
+/// Only requests that actually reached the network are recorded. Each endpoint may be requested
+/// again [`INTERVAL`] after its last request, and every consecutive failure doubles that wait.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Throttle {
+    path: PathBuf,
+    entries: BTreeMap<String, Entry>,
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Formats a duration as e.g. `1h 2m 3s`, leaving out leading zero units.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (h, m, s) = (seconds / 3600, (seconds / 60) % 60, seconds % 60);
+
+    if h > 0 {
+        format!("{h}h {m}m {s}s")
+    } else if m > 0 {
+        format!("{m}m {s}s")
+    } else {
+        format!("{s}s")
+    }
+}
+
+impl Throttle {
+    pub fn load(config: &Config) -> Result<Self> {
+        Self::load_from(config.data_dir.join("throttle"))
+    }
+
+    pub fn load_from(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut entries = BTreeMap::new();
+
+        if path.exists() {
+            for line in std::fs::read_to_string(&path)?.lines() {
+                let mut parts = line.split_whitespace();
+                let (Some(endpoint), Some(last), Some(failures), None) =
+                    (parts.next(), parts.next(), parts.next(), parts.next())
+                else {
+                    return Err(eyre!("Invalid throttle line: {line:?}"));
+                };
+
+                let entry = Entry {
+                    last: last.parse()?,
+                    failures: failures.parse()?,
+                };
+                entries.insert(endpoint.to_string(), entry);
+            }
+        }
+
+        Ok(Self { path, entries })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let contents: String = self
+            .entries
+            .iter()
+            .map(|(endpoint, e)| format!("{endpoint} {} {}\n", e.last, e.failures))
+            .collect();
+        if let Some(root) = self.path.parent().filter(|r| !r.as_os_str().is_empty()) {
+            std::fs::create_dir_all(root)?;
+        }
+        std::fs::write(&self.path, contents)?;
+
+        Ok(())
+    }
+
+    /// How long until `endpoint` may be requested again, if it may not be requested now.
+    pub fn remaining(&self, endpoint: &str, now: SystemTime) -> Option<Duration> {
+        let entry = self.entries.get(endpoint)?;
+
+        let wait = INTERVAL
+            .saturating_mul(2u32.saturating_pow(entry.failures))
+            .min(MAX_BACKOFF);
+        let elapsed = Duration::from_secs(unix_seconds(now).saturating_sub(entry.last));
+
+        wait.checked_sub(elapsed).filter(|d| !d.is_zero())
+    }
+
+    pub fn check(&self, endpoint: &str, now: SystemTime) -> Result<()> {
+        match self.remaining(endpoint, now) {
+            Some(remaining) => {
+                let failures = self.entries[endpoint].failures;
+                let reason = if failures > 0 {
+                    format!(" after {failures} failed attempt(s)")
+                } else {
+                    String::new()
+                };
+
+                Err(eyre!(
+                    "Throttled{reason}: {endpoint} can be requested again in {}",
+                    format_duration(remaining)
+                ))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Records a request that reached the server, successful or not.
+    pub fn record(&mut self, endpoint: &str, success: bool, now: SystemTime) {
+        let failures = match self.entries.get(endpoint) {
+            Some(e) if !success => e.failures + 1,
+            None if !success => 1,
+            _ => 0,
+        };
+
+        self.entries.insert(
+            endpoint.to_string(),
+            Entry {
+                last: unix_seconds(now),
+                failures,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn per_endpoint() {
+        let mut throttle = Throttle::default();
+        throttle.record("day/1/input", true, at(1000));
+
+        assert!(throttle.check("day/1/input", at(1000 + 60)).is_err());
+        assert!(throttle.check("day/2/input", at(1000 + 60)).is_ok());
+        assert_eq!(
+            throttle.remaining("day/1/input", at(1000 + 60)),
+            Some(Duration::from_secs(14 * 60))
+        );
+        assert!(throttle.check("day/1/input", at(1000 + 15 * 60)).is_ok());
+    }
+
+    #[test]
+    fn backoff() {
+        let mut throttle = Throttle::default();
+        throttle.record("day/1/input", false, at(0));
+        throttle.record("day/1/input", false, at(0));
+
+        assert_eq!(
+            throttle.remaining("day/1/input", at(0)),
+            Some(Duration::from_secs(60 * 60))
+        );
+
+        let error = throttle.check("day/1/input", at(30 * 60)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Throttled after 2 failed attempt(s): day/1/input can be requested again in 30m 0s"
+        );
+
+        throttle.record("day/1/input", true, at(60 * 60));
+        assert_eq!(throttle.entries["day/1/input"].failures, 0);
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = std::env::temp_dir().join(format!("throttle-test-{}", std::process::id()));
+
+        let mut throttle = Throttle::load_from(&path).unwrap();
+        throttle.record("day/1/input", false, at(5));
+        throttle.record("day/2/input", true, at(7));
+        throttle.save().unwrap();
+
+        let loaded = Throttle::load_from(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, throttle);
+    }
+}
@@ -0,0 +1,117 @@
+//! Runs every day against the worked examples from the puzzle text.
+//!
+//! Each example lives in `tests/fixtures/NN/<name>.txt`, with the expected answers next to it in
+//! `<name>.answers` as `part1: <answer>` / `part2: <answer>` lines. Parts missing from the answers
+//! file, or not yet implemented according to the registry, are not checked.
+
+use std::path::{Path, PathBuf};
+
+use advent2023::day;
+
+fn fixtures(day: u8) -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(format!("{day:02}"));
+
+    let mut inputs: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("no fixtures for day {day} in {}: {e}", dir.display()))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    inputs.sort();
+
+    assert!(!inputs.is_empty(), "no fixtures for day {day}");
+
+    inputs
+}
+
+fn answers(input: &Path) -> Vec<(String, String)> {
+    let path = input.with_extension("answers");
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("missing {}: {e}", path.display()))
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| {
+            let (part, answer) = l
+                .split_once(':')
+                .unwrap_or_else(|| panic!("malformed line in {}: {l:?}", path.display()));
+            (part.trim().to_string(), answer.trim().to_string())
+        })
+        .collect()
+}
+
+fn check(number: u8) {
+    let info = day::info(number).unwrap();
+    let mut failures = Vec::new();
+
+    for input in fixtures(number) {
+        let name = input.file_stem().unwrap().to_string_lossy().into_owned();
+        let data = std::fs::read_to_string(&input).unwrap();
+
+        let mut solver = info.create();
+        solver.setup(&data).unwrap();
+
+        for (part, expected) in answers(&input) {
+            let actual = match part.as_str() {
+                "part1" if info.part1 => solver.part1(),
+                "part2" if info.part2 => solver.part2(),
+                "part1" | "part2" => continue,
+                _ => panic!("unknown part {part:?} for {name}"),
+            };
+
+            match actual {
+                Ok(actual) if actual == expected => {}
+                Ok(actual) => {
+                    failures.push(format!("{name} {part}: expected {expected}, got {actual}"))
+                }
+                Err(e) => failures.push(format!("{name} {part}: {e}")),
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "day {number} ({}) failed:\n{}",
+        info.title,
+        failures.join("\n")
+    );
+}
+
+macro_rules! examples {
+    ($($name:ident => $day:literal,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                check($day);
+            }
+        )*
+    };
+}
+
+examples! {
+    day01 => 1,
+    day02 => 2,
+    day03 => 3,
+    day04 => 4,
+    day05 => 5,
+    day06 => 6,
+    day07 => 7,
+    day08 => 8,
+    day09 => 9,
+    day10 => 10,
+    day11 => 11,
+    day12 => 12,
+    day13 => 13,
+    day14 => 14,
+    day15 => 15,
+    day16 => 16,
+    day17 => 17,
+    day18 => 18,
+    day19 => 19,
+    day20 => 20,
+    day21 => 21,
+    day22 => 22,
+    day23 => 23,
+    day24 => 24,
+    day25 => 25,
+}